@@ -0,0 +1,76 @@
+// Genera las tablas de syscalls a partir de los .tbl del kernel vendorizados en syscalls/.
+// Produce $OUT_DIR/syscall_tables.rs, incluido por src/sysdecode.rs.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+struct Entry {
+    nr: u64,
+    abi: String,
+    name: String,
+}
+
+/// Parsea un syscall_*.tbl: `<número> <abi> <nombre> [<entry point> ...]`.
+fn parse_tbl(path: &Path) -> Vec<Entry> {
+    let text = fs::read_to_string(path).unwrap_or_else(|e| panic!("no se pudo leer {}: {e}", path.display()));
+    let mut entries = Vec::new();
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut cols = line.split_whitespace();
+        let (Some(nr), Some(abi), Some(name)) = (cols.next(), cols.next(), cols.next()) else {
+            panic!("{}:{}: línea mal formada", path.display(), lineno + 1);
+        };
+        let nr = nr
+            .parse()
+            .unwrap_or_else(|_| panic!("{}:{}: número inválido '{nr}'", path.display(), lineno + 1));
+        entries.push(Entry { nr, abi: abi.to_string(), name: name.to_string() });
+    }
+    entries.sort_by_key(|e| e.nr);
+    entries
+}
+
+/// Emite `<PREFIX>_NAMES` (número → nombre, ordenada por número) y `<PREFIX>_NUMBERS`
+/// (nombre → número, ordenada por nombre) para búsqueda binaria. La tabla inversa
+/// solo incluye las ABIs indicadas (p. ej. sin el rango x32 en x86_64).
+fn emit_table(out: &mut String, prefix: &str, entries: &[Entry], reverse_abis: &[&str]) {
+    out.push_str(&format!("pub static {prefix}_NAMES: &[(u64, &str)] = &[\n"));
+    for e in entries {
+        out.push_str(&format!("    ({}, \"{}\"),\n", e.nr, e.name));
+    }
+    out.push_str("];\n\n");
+
+    let mut by_name: Vec<&Entry> = entries.iter().filter(|e| reverse_abis.contains(&e.abi.as_str())).collect();
+    by_name.sort_by(|a, b| a.name.cmp(&b.name));
+    by_name.dedup_by(|a, b| a.name == b.name);
+    out.push_str(&format!("pub static {prefix}_NUMBERS: &[(&str, u64)] = &[\n"));
+    for e in by_name {
+        out.push_str(&format!("    (\"{}\", {}),\n", e.name, e.nr));
+    }
+    out.push_str("];\n\n");
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let tbl_dir = Path::new(&manifest_dir).join("syscalls");
+    let mut out = String::from("// Generado por build.rs: no editar.\n\n");
+
+    // (archivo, prefijo, ABIs para la tabla inversa nombre → número)
+    let tables: [(&str, &str, &[&str]); 3] = [
+        ("syscall_64.tbl", "X86_64", &["common", "64"]),
+        ("syscall_32.tbl", "I386", &["i386"]),
+        ("syscall_aarch64.tbl", "AARCH64", &["common"]),
+    ];
+    for (file, prefix, reverse_abis) in tables {
+        let path = tbl_dir.join(file);
+        println!("cargo:rerun-if-changed={}", path.display());
+        emit_table(&mut out, prefix, &parse_tbl(&path), reverse_abis);
+    }
+
+    let dest = Path::new(&env::var("OUT_DIR").unwrap()).join("syscall_tables.rs");
+    fs::write(&dest, out).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+}
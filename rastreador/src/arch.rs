@@ -0,0 +1,265 @@
+use nix::unistd::Pid;
+
+use crate::syscall_info::{AUDIT_ARCH_AARCH64, AUDIT_ARCH_I386, AUDIT_ARCH_X86_64};
+
+// === Abstracción de arquitectura ===
+// El tracer trabaja con `Abi` (qué tabla de syscalls y qué convención de llamada usa
+// la syscall en curso) y con el trait `SyscallRegs` (acceso a número, argumentos y
+// retorno sin nombrar registros concretos). Cada arquitectura anfitriona aporta su
+// tipo `Regs` y su `get_regs`.
+
+/// Personalidad de una syscall. En un host x86_64 un mismo tracee puede alternar entre
+/// X86_64 e I386 (binarios de 32 bits, o `int 0x80` desde código de 64 bits).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Abi {
+    X86_64,
+    I386,
+    Aarch64,
+}
+
+impl Abi {
+    /// ABI nativa del host donde corre el rastreador.
+    pub fn native() -> Abi {
+        #[cfg(target_arch = "aarch64")]
+        return Abi::Aarch64;
+        #[cfg(not(target_arch = "aarch64"))]
+        return Abi::X86_64;
+    }
+
+    /// Traduce el AUDIT_ARCH_* que reporta PTRACE_GET_SYSCALL_INFO.
+    pub fn from_audit_arch(arch: u32) -> Option<Abi> {
+        match arch {
+            AUDIT_ARCH_X86_64 => Some(Abi::X86_64),
+            AUDIT_ARCH_I386 => Some(Abi::I386),
+            AUDIT_ARCH_AARCH64 => Some(Abi::Aarch64),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Abi::X86_64 => "x86_64",
+            Abi::I386 => "i386",
+            Abi::Aarch64 => "aarch64",
+        }
+    }
+
+    /// Tamaño de un puntero del tracee (para recorrer argv, iovecs, etc.).
+    pub fn pointer_size(self) -> u64 {
+        match self {
+            Abi::I386 => 4,
+            Abi::X86_64 | Abi::Aarch64 => 8,
+        }
+    }
+}
+
+/// Acceso a los registros de una syscall-stop independiente de la arquitectura. Los
+/// accesos dependientes de la convención de llamada reciben la ABI de la syscall: la
+/// del campo arch de PTRACE_GET_SYSCALL_INFO si está (un `int 0x80` desde código de 64
+/// bits no se ve en CS), o si no `abi()`.
+pub trait SyscallRegs {
+    /// ABI deducida de los registros (respaldo si no hay PTRACE_GET_SYSCALL_INFO).
+    fn abi(&self) -> Abi;
+    fn syscall_number(&self, abi: Abi) -> u64;
+    fn args(&self, abi: Abi) -> [u64; 6];
+    fn return_value(&self, abi: Abi) -> i64;
+    fn instruction_pointer(&self) -> u64;
+    fn stack_pointer(&self) -> u64;
+    /// Cambia el retorno (en un stop de salida; se aplica con `set_regs`).
+    fn set_return_value(&mut self, value: i64);
+    /// Cambia el argumento `i` (desde 0; en un stop de entrada, con `set_regs`).
+    fn set_arg(&mut self, abi: Abi, i: usize, value: u64);
+}
+
+// --- x86_64 (incluye syscalls i386 en modo compatibilidad) ---
+
+#[cfg(target_arch = "x86_64")]
+pub type Regs = libc::user_regs_struct;
+
+#[cfg(target_arch = "x86_64")]
+pub fn get_regs(pid: Pid) -> nix::Result<Regs> {
+    nix::sys::ptrace::getregs(pid)
+}
+
+#[cfg(target_arch = "x86_64")]
+pub fn set_regs(pid: Pid, regs: &Regs) -> nix::Result<()> {
+    nix::sys::ptrace::setregs(pid, *regs)
+}
+
+/// Cambia el número de la syscall en su stop de entrada (con uno inválido, el kernel
+/// no la ejecuta y la salida trae -ENOSYS).
+#[cfg(target_arch = "x86_64")]
+pub fn set_syscall_number(pid: Pid, regs: &mut Regs, nr: u64) -> nix::Result<()> {
+    regs.orig_rax = nr;
+    set_regs(pid, regs)
+}
+
+/// Registros de propósito general en texto (comando `r` del depurador de -V).
+#[cfg(target_arch = "x86_64")]
+pub fn format_regs(r: &Regs) -> String {
+    let regs = [
+        ("rip", r.rip), ("rsp", r.rsp), ("rbp", r.rbp), ("eflags", r.eflags),
+        ("orig_rax", r.orig_rax), ("rax", r.rax), ("rbx", r.rbx), ("rcx", r.rcx),
+        ("rdx", r.rdx), ("rsi", r.rsi), ("rdi", r.rdi), ("r8", r.r8),
+        ("r9", r.r9), ("r10", r.r10), ("r11", r.r11), ("r12", r.r12),
+        ("r13", r.r13), ("r14", r.r14), ("r15", r.r15), ("cs", r.cs),
+    ];
+    let cells: Vec<String> = regs.iter().map(|(n, v)| format!("{:>8}=0x{:016x}", n, v)).collect();
+    cells.chunks(4).map(|row| row.join(" ")).collect::<Vec<_>>().join("\n")
+}
+
+// Selector de segmento de código de 32 bits (__USER32_CS) en Linux x86_64
+#[cfg(target_arch = "x86_64")]
+const USER32_CS: u64 = 0x23;
+
+#[cfg(target_arch = "x86_64")]
+impl SyscallRegs for libc::user_regs_struct {
+    fn abi(&self) -> Abi {
+        if self.cs == USER32_CS { Abi::I386 } else { Abi::X86_64 }
+    }
+
+    fn syscall_number(&self, abi: Abi) -> u64 {
+        // El número está en ORIG_RAX (en i386 solo cuentan los 32 bits bajos)
+        match abi {
+            Abi::I386 => self.orig_rax as u32 as u64,
+            _ => self.orig_rax,
+        }
+    }
+
+    fn args(&self, abi: Abi) -> [u64; 6] {
+        match abi {
+            // i386: ebx, ecx, edx, esi, edi, ebp
+            Abi::I386 => [self.rbx, self.rcx, self.rdx, self.rsi, self.rdi, self.rbp].map(|r| r as u32 as u64),
+            // x86_64: rdi, rsi, rdx, r10, r8, r9
+            _ => [self.rdi, self.rsi, self.rdx, self.r10, self.r8, self.r9],
+        }
+    }
+
+    fn return_value(&self, abi: Abi) -> i64 {
+        match abi {
+            Abi::I386 => self.rax as u32 as i32 as i64, // eax con signo
+            _ => self.rax as i64,
+        }
+    }
+
+    fn instruction_pointer(&self) -> u64 {
+        self.rip
+    }
+
+    fn stack_pointer(&self) -> u64 {
+        self.rsp
+    }
+
+    fn set_return_value(&mut self, value: i64) {
+        self.rax = value as u64;
+    }
+
+    fn set_arg(&mut self, abi: Abi, i: usize, value: u64) {
+        let reg = match (abi, i) {
+            (Abi::I386, 0) => &mut self.rbx,
+            (Abi::I386, 1) => &mut self.rcx,
+            (Abi::I386, 2) => &mut self.rdx,
+            (Abi::I386, 3) => &mut self.rsi,
+            (Abi::I386, 4) => &mut self.rdi,
+            (Abi::I386, _) => &mut self.rbp,
+            (_, 0) => &mut self.rdi,
+            (_, 1) => &mut self.rsi,
+            (_, 2) => &mut self.rdx,
+            (_, 3) => &mut self.r10,
+            (_, 4) => &mut self.r8,
+            (_, _) => &mut self.r9,
+        };
+        *reg = value;
+    }
+}
+
+// --- aarch64 (registros vía PTRACE_GETREGSET/NT_PRSTATUS) ---
+
+#[cfg(target_arch = "aarch64")]
+pub type Regs = libc::user_regs_struct;
+
+#[cfg(target_arch = "aarch64")]
+pub fn get_regs(pid: Pid) -> nix::Result<Regs> {
+    // arm64 no implementa PTRACE_GETREGS: se usa el regset general NT_PRSTATUS
+    let mut regs: libc::user_regs_struct = unsafe { std::mem::zeroed() };
+    let mut iov = libc::iovec {
+        iov_base: &mut regs as *mut libc::user_regs_struct as *mut libc::c_void,
+        iov_len: std::mem::size_of::<libc::user_regs_struct>(),
+    };
+    let res = unsafe {
+        libc::ptrace(libc::PTRACE_GETREGSET, pid.as_raw(), libc::NT_PRSTATUS as libc::c_long, &mut iov as *mut libc::iovec)
+    };
+    nix::errno::Errno::result(res)?;
+    Ok(regs)
+}
+
+/// Registros de propósito general en texto (comando `r` del depurador de -V).
+#[cfg(target_arch = "aarch64")]
+pub fn format_regs(r: &Regs) -> String {
+    let mut cells: Vec<String> = r.regs.iter().enumerate().map(|(i, v)| format!("{:>6}=0x{:016x}", format!("x{i}"), v)).collect();
+    cells.push(format!("{:>6}=0x{:016x}", "sp", r.sp));
+    cells.push(format!("{:>6}=0x{:016x}", "pc", r.pc));
+    cells.push(format!("{:>6}=0x{:016x}", "pstate", r.pstate));
+    cells.chunks(4).map(|row| row.join(" ")).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(target_arch = "aarch64")]
+fn set_regset<T>(pid: Pid, kind: libc::c_int, data: &T) -> nix::Result<()> {
+    let mut iov = libc::iovec { iov_base: data as *const T as *mut libc::c_void, iov_len: std::mem::size_of::<T>() };
+    let res = unsafe { libc::ptrace(libc::PTRACE_SETREGSET, pid.as_raw(), kind as libc::c_long, &mut iov as *mut libc::iovec) };
+    nix::errno::Errno::result(res).map(drop)
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn set_regs(pid: Pid, regs: &Regs) -> nix::Result<()> {
+    set_regset(pid, libc::NT_PRSTATUS, regs)
+}
+
+// Regset con el número de syscall en curso: en arm64 cambiar x8 no basta
+#[cfg(target_arch = "aarch64")]
+const NT_ARM_SYSTEM_CALL: libc::c_int = 0x404;
+
+/// Cambia el número de la syscall en su stop de entrada (con uno inválido, el kernel
+/// no la ejecuta y la salida trae -ENOSYS).
+#[cfg(target_arch = "aarch64")]
+pub fn set_syscall_number(pid: Pid, regs: &mut Regs, nr: u64) -> nix::Result<()> {
+    regs.regs[8] = nr;
+    set_regs(pid, regs)?;
+    set_regset(pid, NT_ARM_SYSTEM_CALL, &(nr as libc::c_int))
+}
+
+#[cfg(target_arch = "aarch64")]
+impl SyscallRegs for libc::user_regs_struct {
+    fn abi(&self) -> Abi {
+        Abi::Aarch64
+    }
+
+    fn syscall_number(&self, _abi: Abi) -> u64 {
+        self.regs[8] // x8
+    }
+
+    fn args(&self, _abi: Abi) -> [u64; 6] {
+        // x0..x5 (en la salida x0 ya fue sobrescrito por el retorno)
+        [self.regs[0], self.regs[1], self.regs[2], self.regs[3], self.regs[4], self.regs[5]]
+    }
+
+    fn return_value(&self, _abi: Abi) -> i64 {
+        self.regs[0] as i64
+    }
+
+    fn instruction_pointer(&self) -> u64 {
+        self.pc
+    }
+
+    fn stack_pointer(&self) -> u64 {
+        self.sp
+    }
+
+    fn set_return_value(&mut self, value: i64) {
+        self.regs[0] = value as u64;
+    }
+
+    fn set_arg(&mut self, _abi: Abi, i: usize, value: u64) {
+        self.regs[i.min(5)] = value;
+    }
+}
@@ -0,0 +1,250 @@
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use nix::unistd::Pid;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use crate::arch::{format_regs, get_regs, set_regs, Abi, SyscallRegs};
+use crate::inject::parse_value;
+use crate::sysdecode::{is_syscall_name, read_bytes};
+use crate::{PENDING_SIGNAL, SIGNAL_FROM_TTY};
+
+// === Depurador interactivo de -V ===
+// Tras cada evento mostrado (entrada o salida de syscall) -V puede detenerse y leer
+// comandos por la terminal. La línea se lee en modo raw con crossterm, como la pausa
+// original, para no competir con el tracee por stdin:
+//   s / Enter   avanza un evento        c        continúa hasta un breakpoint
+//   b NAME      breakpoint en NAME      skip N   deja pasar N eventos
+//   d           desadjunta y termina    x ADDR LEN  vuelca memoria del tracee
+//   r           muestra los registros   set arg N VAL  cambia un argumento (entrada)
+
+const HELP: &str = "comandos: s (paso), c (continuar), b NAME, skip N, d (desadjuntar), x ADDR LEN, r, set arg N VAL";
+const MAX_DUMP: usize = 4096; // bytes máximos de `x`
+
+/// Qué hacer con el tracee al salir del prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Resume,
+    Detach,
+}
+
+/// Stop en el que se detuvo el depurador.
+pub struct Stop<'a> {
+    pub pid: Pid,
+    pub abi: Abi, // la de la syscall en curso (decide en qué registros van los argumentos)
+    pub name: &'a str,
+    pub entering: bool,
+    pub nullified: bool, // entrada ya anulada por -e inject= (el retorno se pone en la salida)
+    pub args: &'a mut [u64; 6], // argumentos de la entrada (los que ve --fd-report, etc.)
+    pub raw_args: Option<&'a mut [u64; 6]>, // los del evento (decodificación de la salida, -y)
+}
+
+#[derive(Debug)]
+pub struct Debugger {
+    stepping: bool,                // con `c` deja de pausar salvo en breakpoints
+    skip: u64,                     // eventos que aún se dejan pasar sin pausa
+    breakpoints: BTreeSet<String>, // syscalls con breakpoint
+}
+
+impl Default for Debugger {
+    fn default() -> Self {
+        // -V arranca pausando en cada evento, como la pausa por tecla original
+        Debugger { stepping: true, skip: 0, breakpoints: BTreeSet::new() }
+    }
+}
+
+impl Debugger {
+    /// ¿Hay que detenerse en este evento de la syscall `name`? Un breakpoint siempre
+    /// detiene (y cancela el skip pendiente), aunque -e trace= excluya la syscall; el
+    /// paso a paso solo se detiene en las que se muestran (`traced`).
+    pub fn should_pause(&mut self, name: &str, traced: bool) -> bool {
+        if self.breakpoints.contains(name) {
+            self.skip = 0;
+            return true;
+        }
+        if !self.stepping || !traced {
+            return false;
+        }
+        if self.skip > 0 {
+            self.skip -= 1;
+            return false;
+        }
+        true
+    }
+
+    /// Lee y ejecuta comandos hasta que uno reanude el tracee.
+    pub fn prompt(&mut self, stop: &mut Stop) -> Action {
+        let place = if stop.entering { "entrada" } else { "salida" };
+        loop {
+            let line = match read_line(&format!("(V) [{} {}] > ", place, stop.name)) {
+                Ok(Some(line)) => line,
+                Ok(None) => return Action::Resume, // Ctrl-C o señal pendiente: el bucle principal la atiende
+                Err(e) => {
+                    // Sin terminal (stdin redirigido y sin tty de control, p. ej. tras
+                    // setsid) no hay quién responda: se sigue como -v
+                    eprintln!("[rastreador] -V no puede leer la terminal ({e}): se continúa sin pausas");
+                    self.stepping = false;
+                    self.breakpoints.clear();
+                    return Action::Resume;
+                }
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] | ["s"] => {
+                    self.stepping = true;
+                    return Action::Resume;
+                }
+                ["c"] => {
+                    self.stepping = false;
+                    return Action::Resume;
+                }
+                // Desadjuntar aquí dejaría al tracee con el -ENOSYS de la syscall anulada
+                ["d"] if stop.nullified => {
+                    eprintln!("-e inject= ya anuló esta syscall: avance a su salida (s) antes de desadjuntar")
+                }
+                ["d"] => return Action::Detach,
+                ["b", name] if is_syscall_name(name) => {
+                    self.breakpoints.insert(name.to_string());
+                    eprintln!("breakpoint en {name}");
+                }
+                ["b", name] => eprintln!("syscall desconocida: {name}"),
+                ["skip", n] => match n.parse::<u64>() {
+                    Ok(n) => {
+                        self.stepping = true;
+                        self.skip = n;
+                        return Action::Resume;
+                    }
+                    Err(_) => eprintln!("skip espera un número de eventos"),
+                },
+                ["x", addr, len] => match (parse_value(addr), len.parse::<usize>()) {
+                    (Ok(addr), Ok(len)) => dump_memory(stop.pid, addr, len.min(MAX_DUMP)),
+                    _ => eprintln!("uso: x ADDR LEN (ADDR en decimal o 0x...)"),
+                },
+                ["r"] => match get_regs(stop.pid) {
+                    Ok(regs) => eprintln!("{}", format_regs(&regs)),
+                    Err(e) => eprintln!("no se pudieron leer los registros: {e}"),
+                },
+                ["set", "arg", n, value] => set_arg(stop, n, value),
+                ["h"] | ["?"] | ["help"] => eprintln!("{HELP}"),
+                _ => eprintln!("comando desconocido: {line}\n{HELP}"),
+            }
+        }
+    }
+}
+
+/// `set arg N VAL`: solo en la entrada, antes de que el kernel lea los argumentos. Se
+/// parte de los registros actuales para no deshacer lo que haya hecho -e inject=.
+fn set_arg(stop: &mut Stop, n: &str, value: &str) {
+    if !stop.entering {
+        eprintln!("set arg solo tiene efecto en la entrada de la syscall");
+        return;
+    }
+    let (Some(i), Ok(value)) = (n.parse::<usize>().ok().filter(|n| (1..=6).contains(n)), parse_value(value)) else {
+        eprintln!("uso: set arg N VAL (N de 1 a 6)");
+        return;
+    };
+    let res = get_regs(stop.pid).and_then(|mut regs| {
+        regs.set_arg(stop.abi, i - 1, value);
+        set_regs(stop.pid, &regs)
+    });
+    match res {
+        Ok(()) => {
+            stop.args[i - 1] = value;
+            if let Some(raw) = stop.raw_args.as_deref_mut() {
+                raw[i - 1] = value;
+            }
+            eprintln!("arg{} = 0x{:x}", i, value);
+        }
+        Err(e) => eprintln!("no se pudo cambiar el argumento: {e}"),
+    }
+}
+
+/// Volcado hexadecimal de 16 bytes por línea con su vista ASCII.
+fn dump_memory(pid: Pid, addr: u64, len: usize) {
+    let bytes = match read_bytes(pid, addr, len) {
+        Ok(bytes) => bytes,
+        Err(_) => return eprintln!("no se pudo leer 0x{:x}", addr),
+    };
+    for (i, row) in bytes.chunks(16).enumerate() {
+        let hex: Vec<String> = row.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = row.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+        eprintln!("0x{:016x}  {:<47}  |{}|", addr + (i * 16) as u64, hex.join(" "), ascii);
+    }
+}
+
+/// Lee una línea de la terminal en modo raw (con eco y retroceso). None si se pulsó
+/// Ctrl-C o llegó una señal mientras se esperaba; error si no hay terminal.
+fn read_line(prompt: &str) -> io::Result<Option<String>> {
+    eprint!("{prompt}");
+    let _ = io::stderr().flush();
+    if let Err(e) = enable_raw_mode() {
+        eprintln!();
+        return Err(e);
+    }
+    let mut line = String::new();
+    let result = loop {
+        // poll con timeout para no quedar bloqueados si llega SIGTERM mientras esperamos
+        if PENDING_SIGNAL.load(Ordering::SeqCst) != 0 {
+            break Ok(None);
+        }
+        match poll(Duration::from_millis(100)) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) => break Err(e),
+        }
+        match read() {
+            // En modo raw Ctrl-C no genera SIGINT: se trata como si hubiera llegado
+            Ok(Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers, .. }))
+                if modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                SIGNAL_FROM_TTY.store(false, Ordering::SeqCst);
+                PENDING_SIGNAL.store(libc::SIGINT, Ordering::SeqCst);
+                break Ok(None);
+            }
+            Ok(Event::Key(KeyEvent { code: KeyCode::Enter, .. })) => break Ok(Some(line)),
+            Ok(Event::Key(KeyEvent { code: KeyCode::Backspace, .. })) => {
+                if line.pop().is_some() {
+                    eprint!("\x08 \x08");
+                }
+            }
+            Ok(Event::Key(KeyEvent { code: KeyCode::Char(c), .. })) => {
+                line.push(c);
+                eprint!("{c}");
+            }
+            Err(e) => break Err(e),
+            Ok(_) => {}
+        }
+        let _ = io::stderr().flush();
+    };
+    let _ = disable_raw_mode();
+    eprintln!();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pausas_y_breakpoints() {
+        let mut dbg = Debugger::default();
+        assert!(dbg.should_pause("read", true));
+        assert!(!dbg.should_pause("read", false));
+
+        // Con `c` solo detienen los breakpoints, también en syscalls no trazadas
+        dbg.stepping = false;
+        dbg.breakpoints.insert("openat".to_string());
+        assert!(!dbg.should_pause("read", true));
+        assert!(dbg.should_pause("openat", false));
+
+        // skip cuenta solo los eventos mostrados y un breakpoint lo cancela
+        dbg.stepping = true;
+        dbg.skip = 2;
+        assert!(!dbg.should_pause("write", false));
+        assert!(!dbg.should_pause("write", true));
+        assert!(dbg.should_pause("openat", true));
+        assert!(dbg.should_pause("write", true));
+    }
+}
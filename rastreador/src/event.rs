@@ -0,0 +1,271 @@
+use nix::unistd::Pid;
+use serde_json::{json, Map, Value};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::arch::Abi;
+use crate::sysdecode::{
+    decode_args, decode_out_args, errno_desc, error_name, fd_args, first_out_arg, format_return, returns_fd, syscall_name, FdCache,
+};
+
+// === Evento de syscall ===
+// Una syscall observada, desde su stop de entrada hasta el de salida. La decodifica
+// sysdecode una sola vez y la consumen por igual el formato de texto (→/←) y --format=jsonl.
+
+/// Cómo terminó una syscall, para -e status= / -z / -Z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallStatus {
+    Successful,
+    Failed,
+    Unfinished, // el tracee terminó sin volver de ella (exit_group, execve de otro hilo)
+}
+
+#[derive(Debug)]
+pub struct SyscallEvent {
+    pub tid: Pid,
+    pub pid: i32, // TGID del proceso al que pertenece el hilo
+    pub abi: Abi,
+    pub nr: u64,
+    pub name: String,
+    pub timestamp: SystemTime,                       // instante de la entrada (reloj de pared)
+    pub relative: Option<Duration>,                  // -r: desde la entrada de la syscall anterior
+    pub raw_args: Option<[u64; 6]>,                  // None si no se vio la entrada
+    pub args: Option<Vec<(&'static str, String)>>,   // None si la syscall no tiene esquema
+    pub ip_sp: Option<(u64, u64)>,                   // con -i
+    pub ret: Option<i64>,
+    pub ret_fd: Option<String>,                      // -y: destino del fd devuelto
+    pub injected: bool,                              // el retorno lo puso -e inject=
+    pub delayed: bool,                               // -e inject=delay_*: se retuvo al tracee
+    pub out_args: Vec<(&'static str, String)>,
+    pub duration: Option<Duration>,
+}
+
+impl SyscallEvent {
+    /// Evento en el stop de entrada: decodifica los argumentos de una vez (los punteros
+    /// de entrada solo son fiables aquí).
+    pub fn enter(tid: Pid, pid: i32, abi: Abi, nr: u64, raw_args: [u64; 6], ip_sp: Option<(u64, u64)>) -> Self {
+        let event = SyscallEvent::unseen(tid, pid, abi, nr);
+        SyscallEvent {
+            args: decode_args(tid, abi, &event.name, &raw_args),
+            raw_args: Some(raw_args),
+            ip_sp,
+            ..event
+        }
+    }
+
+    /// Evento de una salida cuya entrada no se vio (p. ej. al adjuntar con -p a mitad de
+    /// una syscall): sin argumentos ni duración.
+    pub fn unseen(tid: Pid, pid: i32, abi: Abi, nr: u64) -> Self {
+        SyscallEvent {
+            tid,
+            pid,
+            abi,
+            nr,
+            name: syscall_name(abi, nr),
+            timestamp: SystemTime::now(),
+            relative: None,
+            raw_args: None,
+            args: None,
+            ip_sp: None,
+            ret: None,
+            ret_fd: None,
+            injected: false,
+            delayed: false,
+            out_args: Vec::new(),
+            duration: None,
+        }
+    }
+
+    /// Completa el evento en el stop de salida: retorno, parámetros de salida y el tiempo
+    /// entre los stops de entrada y salida (None si no se vio la entrada).
+    pub fn complete(&mut self, ret: i64, duration: Option<Duration>) {
+        self.ret = Some(ret);
+        self.duration = duration;
+        if let Some(raw) = &self.raw_args {
+            self.out_args = decode_out_args(self.tid, self.abi, &self.name, raw, ret);
+        }
+    }
+
+    /// -y en la entrada: añade a cada argumento fd su destino (`3</etc/passwd>`).
+    pub fn annotate_fds(&mut self, fds: &mut FdCache) {
+        let (Some(args), Some(raw)) = (self.args.as_mut(), self.raw_args) else {
+            return;
+        };
+        for i in fd_args(self.abi, &self.name) {
+            let fd = raw[i] as i32;
+            if fd < 0 {
+                continue; // AT_FDCWD o fd inválido
+            }
+            if let Some(desc) = fds.describe(self.tid, self.pid, fd) {
+                args[i].1 = format!("{}<{}>", args[i].1, desc);
+            }
+        }
+    }
+
+    /// -y en la salida: destino del fd que devolvió la syscall (open, socket, dup...).
+    pub fn annotate_ret_fd(&mut self, fds: &mut FdCache) {
+        if let Some(ret) = self.ret.filter(|&r| r >= 0 && returns_fd(self.abi, &self.name)) {
+            self.ret_fd = fds.describe(self.tid, self.pid, ret as i32);
+        }
+    }
+
+    /// Retorno ya formateado, con el destino del fd si se anotó.
+    fn ret_text(&self) -> String {
+        let ret = format_return(self.abi, &self.name, self.ret.unwrap_or_default());
+        match &self.ret_fd {
+            Some(desc) => format!("{}<{}>", ret, desc),
+            None => ret,
+        }
+    }
+
+    /// Marca de los retornos inyectados o demorados (` (INJECTED)`, ` (DELAYED)` como en strace).
+    fn injected_mark(&self) -> &'static str {
+        match (self.injected, self.delayed) {
+            (true, true) => " (INJECTED) (DELAYED)",
+            (true, false) => " (INJECTED)",
+            (false, true) => " (DELAYED)",
+            (false, false) => "",
+        }
+    }
+
+    /// Clasificación según el retorno: error si está en [-4095, -1].
+    pub fn status(&self) -> SyscallStatus {
+        match self.ret {
+            None => SyscallStatus::Unfinished,
+            Some(ret) if error_name(ret).is_some() => SyscallStatus::Failed,
+            Some(_) => SyscallStatus::Successful,
+        }
+    }
+
+    /// Instante del stop de salida (o el de entrada si aún no se completó).
+    pub fn exit_time(&self) -> SystemTime {
+        self.timestamp + self.duration.unwrap_or_default()
+    }
+
+    /// `name(a=1, b=2)`; sin esquema, las seis palabras crudas en hex.
+    pub fn call_text(&self) -> String {
+        let parts: Vec<String> = match (&self.args, &self.raw_args) {
+            (Some(args), _) => args.iter().map(|(n, v)| format!("{}={}", n, v)).collect(),
+            (None, Some(raw)) => raw.iter().map(|a| format!("0x{:x}", a)).collect(),
+            (None, None) => vec!["?".to_string()],
+        };
+        format!("{}({})", self.name, parts.join(", "))
+    }
+
+    /// `name = ret (out1, out2)` para la línea de salida del formato de texto.
+    pub fn return_text(&self) -> String {
+        let ret = self.ret_text();
+        if self.out_args.is_empty() {
+            format!("{} = {}{}", self.name, ret, self.injected_mark())
+        } else {
+            let outs: Vec<String> = self.out_args.iter().map(|(n, v)| format!("{}={}", n, v)).collect();
+            format!("{} = {} ({}){}", self.name, ret, outs.join(", "), self.injected_mark())
+        }
+    }
+
+    /// Argumentos al estilo strace: solo valores, con los parámetros de salida ya
+    /// decodificados en su posición (`read(3, "\177ELF"..., 832)`).
+    pub fn strace_args(&self) -> String {
+        self.strace_parts().join(", ")
+    }
+
+    /// Los mismos argumentos partidos como strace cuando otra línea corta la syscall: la
+    /// entrada llega hasta el primer parámetro de salida (`wait4(-1, <unfinished ...>`)
+    /// y `<... wait4 resumed>` sigue desde ahí (`[{WIFEXITED(s) ...}], 0, NULL) = 42`).
+    pub fn strace_split(&self) -> (String, String) {
+        let parts = self.strace_parts();
+        match first_out_arg(self.abi, &self.name).filter(|&i| i < parts.len()) {
+            Some(0) => (String::new(), parts.join(", ")),
+            Some(i) => (format!("{}, ", parts[..i].join(", ")), parts[i..].join(", ")),
+            None => (parts.join(", "), String::new()),
+        }
+    }
+
+    fn strace_parts(&self) -> Vec<String> {
+        match (&self.args, &self.raw_args) {
+            (Some(args), _) => args
+                .iter()
+                .map(|(n, v)| self.out_args.iter().find(|(o, _)| o == n).map_or(v, |(_, o)| o).clone())
+                .collect(),
+            (None, Some(raw)) => raw.iter().map(|a| format!("0x{:x}", a)).collect(),
+            (None, None) => Vec::new(),
+        }
+    }
+
+    /// Retorno al estilo strace: `= 3`, `= -1 ENOENT (No such file or directory)`.
+    pub fn strace_ret(&self) -> String {
+        match self.ret {
+            None => "= ?".to_string(),
+            Some(ret) => match error_name(ret) {
+                Some(errno) => format!("= -1 {} ({}){}", errno, errno_desc(-ret as i32), self.injected_mark()),
+                None => format!("= {}{}", self.ret_text(), self.injected_mark()),
+            },
+        }
+    }
+
+    /// Objeto JSON (una línea de --format=jsonl). Los valores decodificados van como
+    /// texto, tal como los muestra el formato de texto.
+    pub fn to_json(&self) -> Value {
+        let ts = self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        // Sin esquema no se sabe cuántos argumentos usa la syscall: se emiten los seis
+        let raw_args: Option<Vec<u64>> = self.raw_args.map(|raw| {
+            let n = self.args.as_ref().map_or(6, |a| a.len());
+            raw[..n].to_vec()
+        });
+        let to_map = |pairs: &[(&str, String)]| -> Map<String, Value> {
+            pairs.iter().map(|(n, v)| (n.to_string(), Value::String(v.clone()))).collect()
+        };
+        let mut obj = json!({
+            "type": "syscall",
+            "pid": self.pid,
+            "tid": self.tid.as_raw(),
+            "timestamp": ts,
+            "abi": self.abi.name(),
+            "nr": self.nr,
+            "name": self.name,
+            "raw_args": raw_args,
+            "args": self.args.as_deref().map(to_map),
+            "out_args": to_map(&self.out_args),
+            "ret": self.ret,
+            "errno": self.ret.and_then(error_name),
+            "duration": self.duration.map(|d| d.as_secs_f64()),
+        });
+        if self.injected {
+            obj["injected"] = json!(true);
+        }
+        if self.delayed {
+            obj["delayed"] = json!(true);
+        }
+        if let Some(desc) = &self.ret_fd {
+            obj["ret_fd"] = json!(desc);
+        }
+        if let Some(rel) = self.relative {
+            obj["relative"] = json!(rel.as_secs_f64());
+        }
+        if let Some((ip, sp)) = self.ip_sp {
+            obj["ip"] = json!(ip);
+            obj["sp"] = json!(sp);
+        }
+        obj
+    }
+}
+
+/// Marca de tiempo de -t (`HH:MM:SS`, hora local), -tt (con microsegundos) o -ttt
+/// (segundos desde epoch con microsegundos).
+pub fn fmt_timestamp(ts: SystemTime, level: u8) -> String {
+    let since_epoch = ts.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let (secs, micros) = (since_epoch.as_secs(), since_epoch.subsec_micros());
+    if level >= 3 {
+        return format!("{}.{:06}", secs, micros);
+    }
+    // SAFETY: localtime_r solo escribe en `tm`, que es una struct POD local
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let t = secs as libc::time_t;
+    unsafe { libc::localtime_r(&t, &mut tm) };
+    let hms = format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec);
+    if level == 2 { format!("{}.{:06}", hms, micros) } else { hms }
+}
+
+/// Duración como la muestra strace con -r/-T: segundos con seis decimales.
+pub fn fmt_duration(d: Duration) -> String {
+    format!("{}.{:06}", d.as_secs(), d.subsec_micros())
+}
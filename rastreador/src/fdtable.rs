@@ -0,0 +1,393 @@
+use nix::unistd::Pid;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use crate::arch::Abi;
+use crate::summary::io_fds;
+use crate::sysdecode::{fd_path, read_bytes, returns_fd};
+
+// === Modelo de la tabla de fds de cada proceso (--fd-report) ===
+// Se reconstruye interpretando las syscalls que crean, duplican o cierran fds (con la
+// semántica de O_CLOEXEC en execve) en lugar de leer /proc en cada paso: así se sabe
+// quién abrió cada fd y se detectan los que nunca se cierran, los que se filtran a
+// otro programa por un execve sin O_CLOEXEC y los close dobles o sobre fds inválidos.
+// Los fds que ya existían al empezar el rastreo se leen de /proc y no se reportan.
+
+const F_DUPFD: u64 = libc::F_DUPFD as u64;
+const F_DUPFD_CLOEXEC: u64 = libc::F_DUPFD_CLOEXEC as u64;
+const F_SETFD: u64 = libc::F_SETFD as u64;
+const CLOSE_RANGE_CLOEXEC: u64 = 1 << 2;
+const CLOEXEC: u64 = libc::O_CLOEXEC as u64; // igual a SOCK_CLOEXEC, EFD_CLOEXEC...
+
+/// Syscalls (además de las de E/S) en las que un EBADF delata un close doble o un uso
+/// tras cerrar. Otras fallan con EBADF a propósito: `fcntl(fd, F_GETFD)` es la forma
+/// habitual de preguntar si un fd existe.
+const FD_USES: &[&str] = &["close", "dup", "dup2", "dup3"];
+
+/// 0-2 se heredan y se redirigen a propósito (`dup2(x, 1)`): nunca cuentan como
+/// filtrados ni como sin cerrar.
+fn reportable(fd: i32, info: &FdInfo) -> bool {
+    fd > 2 && info.owner != 0
+}
+
+#[derive(Debug, Clone)]
+struct FdInfo {
+    origin: String, // syscall que lo creó ("previo" si ya existía)
+    target: String, // destino según /proc al crearlo
+    cloexec: bool,
+    owner: i32,     // TGID que lo creó (un fork copia la tabla, pero no la autoría)
+}
+
+#[derive(Debug, Default, Clone)]
+struct Table {
+    fds: BTreeMap<i32, FdInfo>,
+    closed: HashMap<i32, String>, // fd → destino que tenía al cerrarse
+}
+
+/// Problema detectado en la tabla de un proceso.
+#[derive(Debug, Clone)]
+enum Finding {
+    Unclosed { pid: i32, fd: i32, info: FdInfo },
+    ExecLeak { pid: i32, fd: i32, info: FdInfo, program: String },
+    BadFd { pid: i32, syscall: String, fd: i32, closed: Option<String> },
+}
+
+#[derive(Debug, Default)]
+pub struct FdTables {
+    procs: HashMap<i32, Table>,
+    findings: Vec<Finding>,
+}
+
+impl FdTables {
+    /// Tabla del proceso; la primera vez se carga de /proc con los fds ya abiertos.
+    fn table(&mut self, tid: Pid, tgid: i32) -> &mut Table {
+        self.procs.entry(tgid).or_insert_with(|| {
+            let mut table = Table::default();
+            let fds = std::fs::read_dir(format!("/proc/{}/fd", tid)).into_iter().flatten().flatten();
+            for fd in fds.filter_map(|e| e.file_name().to_str()?.parse::<i32>().ok()) {
+                let info = FdInfo {
+                    origin: "previo".to_string(),
+                    target: fd_path(tid, fd).unwrap_or_default(),
+                    cloexec: cloexec_of(tid, fd),
+                    owner: 0,
+                };
+                table.fds.insert(fd, info);
+            }
+            table
+        })
+    }
+
+    /// fork/vfork/clone sin CLONE_THREAD: el hijo hereda una copia de la tabla.
+    pub fn fork(&mut self, parent: Pid, parent_tgid: i32, child_tgid: i32) {
+        let table = self.table(parent, parent_tgid).clone();
+        self.procs.insert(child_tgid, Table { closed: HashMap::new(), ..table });
+    }
+
+    /// Aplica una syscall ya terminada (con los argumentos de su entrada).
+    pub fn syscall(&mut self, tid: Pid, tgid: i32, abi: Abi, name: &str, args: &[u64; 6], ret: i64) {
+        if ret == -(libc::EBADF as i64) {
+            // Solo cuenta si el modelo también da el fd por cerrado
+            let table = self.table(tid, tgid);
+            let used: Vec<usize> = if FD_USES.contains(&name) {
+                vec![0]
+            } else {
+                io_fds(name).iter().map(|&(i, _)| i).collect()
+            };
+            if let Some(fd) = used.into_iter().map(|i| args[i] as i32).find(|fd| !table.fds.contains_key(fd)) {
+                let closed = table.closed.get(&fd).cloned();
+                let syscall = name.to_string();
+                self.findings.push(Finding::BadFd { pid: tgid, syscall, fd, closed });
+            }
+            return;
+        }
+        if ret < 0 {
+            return;
+        }
+        let new = |fd: i32, cloexec: bool| FdInfo {
+            origin: name.to_string(),
+            target: fd_path(tid, fd).unwrap_or_default(),
+            cloexec,
+            owner: tgid,
+        };
+        let fd = ret as i32; // fd nuevo en las que lo devuelven
+        let table = self.table(tid, tgid);
+        match name {
+            "open" => table.open(fd, new(fd, args[1] & CLOEXEC != 0)),
+            "creat" | "accept" => table.open(fd, new(fd, false)),
+            "openat" => table.open(fd, new(fd, args[2] & CLOEXEC != 0)),
+            "socket" => table.open(fd, new(fd, args[1] & CLOEXEC != 0)),
+            "accept4" => table.open(fd, new(fd, args[3] & CLOEXEC != 0)),
+            "dup" => table.dup_or_open(args[0] as i32, fd, new(fd, false)),
+            "dup2" | "dup3" => {
+                let (old, fd) = (args[0] as i32, args[1] as i32);
+                if old != fd {
+                    let cloexec = name == "dup3" && args[2] & CLOEXEC != 0;
+                    table.dup_or_open(old, fd, new(fd, cloexec));
+                }
+            }
+            "fcntl" | "fcntl64" => match args[1] {
+                F_DUPFD | F_DUPFD_CLOEXEC => {
+                    table.dup_or_open(args[0] as i32, fd, new(fd, args[1] == F_DUPFD_CLOEXEC));
+                }
+                F_SETFD => {
+                    if let Some(info) = table.fds.get_mut(&(args[0] as i32)) {
+                        info.cloexec = args[2] & libc::FD_CLOEXEC as u64 != 0;
+                    }
+                }
+                _ => {}
+            },
+            "pipe" | "pipe2" | "socketpair" => {
+                let (ptr, flags) = match name {
+                    "pipe" => (args[0], 0),
+                    "pipe2" => (args[0], args[1]),
+                    _ => (args[3], args[1]),
+                };
+                if let Ok(buf) = read_bytes(tid, ptr, 8) {
+                    for half in buf.chunks_exact(4) {
+                        let fd = i32::from_ne_bytes([half[0], half[1], half[2], half[3]]);
+                        table.open(fd, new(fd, flags & CLOEXEC != 0));
+                    }
+                }
+            }
+            "close" => table.close(args[0] as i32),
+            "close_range" => {
+                let (first, last) = (args[0] as u32 as i32, args[1] as u32);
+                let fds: Vec<i32> = table.fds.range(first..).map(|(&fd, _)| fd).filter(|&fd| fd as u32 <= last).collect();
+                for fd in fds {
+                    if args[2] & CLOSE_RANGE_CLOEXEC != 0 {
+                        table.fds.entry(fd).and_modify(|i| i.cloexec = true);
+                    } else {
+                        table.close(fd);
+                    }
+                }
+            }
+            "execve" | "execveat" => {
+                table.fds.retain(|_, info| !info.cloexec);
+                table.closed.clear();
+                let leaked: Vec<(i32, FdInfo)> = table
+                    .fds
+                    .iter()
+                    .filter(|&(&fd, info)| reportable(fd, info))
+                    .map(|(&fd, info)| (fd, info.clone()))
+                    .collect();
+                let program = std::fs::read_link(format!("/proc/{}/exe", tid))
+                    .map_or_else(|_| "?".to_string(), |p| p.to_string_lossy().into_owned());
+                for (fd, info) in leaked {
+                    self.findings.push(Finding::ExecLeak { pid: tgid, fd, info, program: program.clone() });
+                }
+            }
+            // Cualquier otra que devuelva un fd (epoll_create1, eventfd2, memfd_create...):
+            // sus flags de CLOEXEC varían, así que se consulta /proc/PID/fdinfo
+            _ if returns_fd(abi, name) => table.open(fd, new(fd, cloexec_of(tid, fd))),
+            _ => {}
+        }
+    }
+
+    /// El proceso terminó: los fds que abrió y siguen en su tabla nunca se cerraron.
+    pub fn process_exit(&mut self, tgid: i32) {
+        if let Some(table) = self.procs.remove(&tgid) {
+            for (fd, info) in table.fds.into_iter().filter(|(fd, i)| i.owner == tgid && reportable(*fd, i)) {
+                self.findings.push(Finding::Unclosed { pid: tgid, fd, info });
+            }
+        }
+    }
+
+    /// Hallazgos para el reporte: al terminar el rastreo los procesos aún vivos cuentan
+    /// como terminados (sin tocar el modelo).
+    fn all_findings(&self) -> Vec<Finding> {
+        let mut findings = self.findings.clone();
+        for (&tgid, table) in &self.procs {
+            for (&fd, info) in table.fds.iter().filter(|&(&fd, i)| i.owner == tgid && reportable(fd, i)) {
+                findings.push(Finding::Unclosed { pid: tgid, fd, info: info.clone() });
+            }
+        }
+        findings
+    }
+}
+
+impl Table {
+    fn open(&mut self, fd: i32, info: FdInfo) {
+        self.closed.remove(&fd);
+        self.fds.insert(fd, info);
+    }
+
+    /// Duplicado de `old` en `fd`: hereda el destino del original si se conoce.
+    fn dup_or_open(&mut self, old: i32, fd: i32, mut info: FdInfo) {
+        if info.target.is_empty() {
+            if let Some(orig) = self.fds.get(&old) {
+                info.target = orig.target.clone();
+            }
+        }
+        self.open(fd, info);
+    }
+
+    fn close(&mut self, fd: i32) {
+        if let Some(info) = self.fds.remove(&fd) {
+            self.closed.insert(fd, info.target);
+        }
+    }
+}
+
+/// Bit O_CLOEXEC del fd según /proc/PID/fdinfo (campo `flags:` en octal).
+fn cloexec_of(pid: Pid, fd: i32) -> bool {
+    std::fs::read_to_string(format!("/proc/{}/fdinfo/{}", pid, fd))
+        .ok()
+        .and_then(|s| s.lines().find_map(|l| l.strip_prefix("flags:")).and_then(|v| u64::from_str_radix(v.trim(), 8).ok()))
+        .is_some_and(|flags| flags & CLOEXEC != 0)
+}
+
+pub fn print_fd_report(w: &mut dyn Write, tables: &FdTables) -> io::Result<()> {
+    let findings = tables.all_findings();
+    writeln!(w, "\n===== TABLA DE FDS =====")?;
+    let unclosed: Vec<_> = findings.iter().filter(|f| matches!(f, Finding::Unclosed { .. })).collect();
+    let leaks: Vec<_> = findings.iter().filter(|f| matches!(f, Finding::ExecLeak { .. })).collect();
+    let bad: Vec<_> = findings.iter().filter(|f| matches!(f, Finding::BadFd { .. })).collect();
+    writeln!(w, "Fds sin cerrar: {}", unclosed.len())?;
+    for f in &unclosed {
+        if let Finding::Unclosed { pid, fd, info } = f {
+            writeln!(w, "  [pid {:>6}] fd {:<4} {:<14} {}", pid, fd, info.origin, info.target)?;
+        }
+    }
+    writeln!(w, "Fds filtrados en execve (sin O_CLOEXEC): {}", leaks.len())?;
+    for f in &leaks {
+        if let Finding::ExecLeak { pid, fd, info, program } = f {
+            writeln!(w, "  [pid {:>6}] fd {:<4} {:<14} {} → {}", pid, fd, info.origin, info.target, program)?;
+        }
+    }
+    writeln!(w, "Close dobles / EBADF: {}", bad.len())?;
+    for f in &bad {
+        if let Finding::BadFd { pid, syscall, fd, closed } = f {
+            match closed {
+                Some(target) if syscall == "close" => {
+                    writeln!(w, "  [pid {:>6}] close({}) doble: ya se había cerrado ({})", pid, fd, target)?
+                }
+                Some(target) => writeln!(w, "  [pid {:>6}] {}({}) tras cerrarlo ({})", pid, syscall, fd, target)?,
+                None => writeln!(w, "  [pid {:>6}] {}({}) = EBADF", pid, syscall, fd)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Versión de --format=jsonl: un objeto `{"type":"fd_report",...}` tras el resumen.
+pub fn print_fd_report_json(w: &mut dyn Write, tables: &FdTables) -> io::Result<()> {
+    let mut unclosed = Vec::new();
+    let mut exec_leaks = Vec::new();
+    let mut bad_fds = Vec::new();
+    for f in tables.all_findings() {
+        match f {
+            Finding::Unclosed { pid, fd, info } => {
+                unclosed.push(json!({"pid": pid, "fd": fd, "origin": info.origin, "target": info.target}))
+            }
+            Finding::ExecLeak { pid, fd, info, program } => exec_leaks.push(
+                json!({"pid": pid, "fd": fd, "origin": info.origin, "target": info.target, "program": program}),
+            ),
+            Finding::BadFd { pid, syscall, fd, closed } => {
+                bad_fds.push(json!({"pid": pid, "syscall": syscall, "fd": fd, "closed_target": closed}))
+            }
+        }
+    }
+    let report = json!({
+        "type": "fd_report",
+        "unclosed": unclosed,
+        "exec_leaks": exec_leaks,
+        "bad_fds": bad_fds,
+    });
+    writeln!(w, "{}", report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TID sin entrada en /proc: la tabla empieza vacía y los destinos quedan sin leer
+    const TID: i32 = 0x7fff_fff0;
+    const EBADF: i64 = -(libc::EBADF as i64);
+
+    fn call(t: &mut FdTables, name: &str, args: [u64; 6], ret: i64) {
+        t.syscall(Pid::from_raw(TID), TID, Abi::X86_64, name, &args, ret);
+    }
+
+    fn openat(t: &mut FdTables, fd: i64, flags: u64) {
+        call(t, "openat", [libc::AT_FDCWD as u64, 0, flags, 0, 0, 0], fd);
+    }
+
+    fn bad_fds(t: &FdTables) -> Vec<(String, i32, bool)> {
+        let bad = t.all_findings().into_iter().filter_map(|f| match f {
+            Finding::BadFd { syscall, fd, closed, .. } => Some((syscall, fd, closed.is_some())),
+            _ => None,
+        });
+        bad.collect()
+    }
+
+    fn fds_of(t: &FdTables, unclosed: bool) -> Vec<i32> {
+        let mut fds: Vec<i32> = t
+            .all_findings()
+            .into_iter()
+            .filter_map(|f| match f {
+                Finding::Unclosed { fd, .. } if unclosed => Some(fd),
+                Finding::ExecLeak { fd, .. } if !unclosed => Some(fd),
+                _ => None,
+            })
+            .collect();
+        fds.sort();
+        fds
+    }
+
+    #[test]
+    fn close_doble() {
+        let mut t = FdTables::default();
+        openat(&mut t, 3, 0);
+        call(&mut t, "close", [3, 0, 0, 0, 0, 0], 0);
+        call(&mut t, "close", [3, 0, 0, 0, 0, 0], EBADF);
+        // Un fd que el modelo nunca vio abierto, y un EBADF buscado (fcntl F_GETFD)
+        call(&mut t, "close", [9, 0, 0, 0, 0, 0], EBADF);
+        call(&mut t, "fcntl", [3, libc::F_GETFD as u64, 0, 0, 0, 0], EBADF);
+        assert_eq!(bad_fds(&t), [("close".to_string(), 3, true), ("close".to_string(), 9, false)]);
+        assert!(fds_of(&t, true).is_empty());
+    }
+
+    #[test]
+    fn dup_sin_cloexec_se_filtra_en_execve() {
+        let mut t = FdTables::default();
+        openat(&mut t, 3, CLOEXEC);
+        call(&mut t, "dup2", [3, 5, 0, 0, 0, 0], 5);
+        call(&mut t, "dup3", [3, 6, CLOEXEC, 0, 0, 0], 6);
+        call(&mut t, "fcntl", [3, F_DUPFD, 10, 0, 0, 0], 10);
+        call(&mut t, "fcntl", [10, F_SETFD, libc::FD_CLOEXEC as u64, 0, 0, 0], 0);
+        call(&mut t, "dup2", [3, 1, 0, 0, 0, 0], 1); // redirigir stdio no cuenta
+        call(&mut t, "execve", [0; 6], 0);
+        assert_eq!(fds_of(&t, false), [5]);
+        // Tras el execve solo sigue abierto el filtrado (y el stdio, que no se reporta)
+        t.process_exit(TID);
+        assert_eq!(fds_of(&t, true), [5]);
+    }
+
+    #[test]
+    fn close_range() {
+        let mut t = FdTables::default();
+        for fd in 3..=6 {
+            openat(&mut t, fd, 0);
+        }
+        call(&mut t, "close_range", [5, u32::MAX as u64, 0, 0, 0, 0], 0);
+        call(&mut t, "close_range", [3, 3, CLOSE_RANGE_CLOEXEC, 0, 0, 0], 0);
+        call(&mut t, "close", [6, 0, 0, 0, 0, 0], EBADF);
+        assert_eq!(bad_fds(&t), [("close".to_string(), 6, true)]);
+        call(&mut t, "execve", [0; 6], 0);
+        assert_eq!(fds_of(&t, false), [4]);
+        assert_eq!(fds_of(&t, true), [4]);
+    }
+
+    #[test]
+    fn stdio_no_cuenta_como_sin_cerrar() {
+        let mut t = FdTables::default();
+        openat(&mut t, 1, 0);
+        openat(&mut t, 2, 0);
+        openat(&mut t, 7, 0);
+        assert_eq!(fds_of(&t, true), [7]);
+        t.process_exit(TID);
+        assert_eq!(fds_of(&t, true), [7]);
+        assert!(t.procs.is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use nix::unistd::Pid;
+use regex::Regex;
+
+use crate::arch::Abi;
+use crate::event::SyscallStatus;
+use crate::inject::InjectRule;
+use crate::sysdecode::{is_syscall_name, referenced_paths, SyscallClass};
+
+// === Expresiones de calificación (-e) ===
+// Como en strace, cada -e es `CALIFICADOR=VALOR` (sin calificador se asume trace=).
+// trace= acepta una lista separada por comas de nombres, clases (%file, %network...),
+// regexes (/patrón) y all/none; status= una lista de successful, failed y unfinished.
+// En ambos un `!` inicial niega el conjunto completo. inject= se interpreta en inject.rs.
+
+/// Un elemento de la lista de trace=.
+#[derive(Debug)]
+enum Item {
+    All,
+    Name(String),
+    Class(SyscallClass),
+    Regex(Regex),
+}
+
+/// Conjunto de syscalls a mostrar (afecta a la traza y al resumen).
+#[derive(Debug)]
+pub struct TraceFilter {
+    negated: bool,
+    items: Vec<Item>,
+}
+
+impl Default for TraceFilter {
+    fn default() -> Self {
+        TraceFilter { negated: false, items: vec![Item::All] }
+    }
+}
+
+impl TraceFilter {
+    pub fn parse(expr: &str) -> anyhow::Result<TraceFilter> {
+        let (negated, list) = match expr.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, expr),
+        };
+        let mut items = Vec::new();
+        for tok in list.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let item = if tok == "all" {
+                Item::All
+            } else if tok == "none" {
+                continue; // conjunto vacío
+            } else if let Some(class) = tok.strip_prefix('%') {
+                Item::Class(
+                    SyscallClass::from_name(class).ok_or_else(|| anyhow::anyhow!("clase de syscalls desconocida: %{class}"))?,
+                )
+            } else if let Some(pattern) = tok.strip_prefix('/') {
+                Item::Regex(Regex::new(pattern).map_err(|e| anyhow::anyhow!("regex inválida '{pattern}': {e}"))?)
+            } else if is_syscall_name(tok) {
+                Item::Name(tok.to_string())
+            } else {
+                anyhow::bail!("syscall desconocida en -e trace=: {tok}");
+            };
+            items.push(item);
+        }
+        Ok(TraceFilter { negated, items })
+    }
+
+    /// ¿Se traza la syscall `name` de la ABI `abi`? Los nombres y regex valen igual para
+    /// todas las ABIs; %file y %desc dependen del esquema de cada una.
+    pub fn matches(&self, abi: Abi, name: &str) -> bool {
+        let hit = self.items.iter().any(|item| match item {
+            Item::All => true,
+            Item::Name(n) => n == name,
+            Item::Class(class) => class.contains(abi, name),
+            Item::Regex(re) => re.is_match(name),
+        });
+        hit != self.negated
+    }
+}
+
+/// Estados de retorno a mostrar (-e status=, -z, -Z).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusFilter {
+    successful: bool,
+    failed: bool,
+    unfinished: bool,
+}
+
+impl Default for StatusFilter {
+    fn default() -> Self {
+        StatusFilter { successful: true, failed: true, unfinished: true }
+    }
+}
+
+impl StatusFilter {
+    pub fn parse(expr: &str) -> anyhow::Result<StatusFilter> {
+        let (negated, list) = match expr.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, expr),
+        };
+        let mut f = StatusFilter { successful: false, failed: false, unfinished: false };
+        for tok in list.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            match tok {
+                "all" => f = StatusFilter::default(),
+                "none" => {}
+                "successful" => f.successful = true,
+                "failed" => f.failed = true,
+                "unfinished" => f.unfinished = true,
+                _ => anyhow::bail!("estado desconocido en -e status=: {tok}"),
+            }
+        }
+        if negated {
+            f = StatusFilter { successful: !f.successful, failed: !f.failed, unfinished: !f.unfinished };
+        }
+        Ok(f)
+    }
+
+    /// Solo con un filtro activo hay que esperar a la salida para decidir si se imprime.
+    pub fn is_all(&self) -> bool {
+        *self == StatusFilter::default()
+    }
+
+    /// Estados que admiten a la vez los dos filtros (-Z/-z combinados con -e status=).
+    pub fn intersect(self, other: StatusFilter) -> StatusFilter {
+        StatusFilter {
+            successful: self.successful && other.successful,
+            failed: self.failed && other.failed,
+            unfinished: self.unfinished && other.unfinished,
+        }
+    }
+
+    pub fn matches(&self, status: SyscallStatus) -> bool {
+        match status {
+            SyscallStatus::Successful => self.successful,
+            SyscallStatus::Failed => self.failed,
+            SyscallStatus::Unfinished => self.unfinished,
+        }
+    }
+}
+
+/// Rutas de -P: solo se muestran las syscalls que se refieren a alguna de ellas.
+#[derive(Debug, Default)]
+pub struct PathFilter {
+    paths: Vec<String>, // como se dieron y, si existen, canonicalizadas
+}
+
+impl PathFilter {
+    pub fn new(paths: &[String]) -> PathFilter {
+        let mut all = Vec::new();
+        for p in paths {
+            all.push(p.clone());
+            // Con enlaces simbólicos el tracee puede nombrar el destino real
+            if let Ok(real) = std::fs::canonicalize(p) {
+                all.push(real.to_string_lossy().into_owned());
+            }
+        }
+        PathFilter { paths: all }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// ¿Se refiere la syscall (en su entrada) a alguna de las rutas? Sin -P, siempre.
+    pub fn matches(&self, pid: Pid, abi: Abi, name: &str, args: &[u64; 6]) -> bool {
+        self.is_empty() || referenced_paths(pid, abi, name, args).iter().any(|p| self.paths.contains(p))
+    }
+}
+
+/// Calificadores de -e (y -P) ya interpretados.
+#[derive(Debug, Default)]
+pub struct Qualifiers {
+    pub trace: TraceFilter,
+    pub status: StatusFilter,
+    pub paths: PathFilter,
+    pub inject: Vec<InjectRule>,
+}
+
+impl Qualifiers {
+    /// Interpreta todos los -e en orden; un calificador repetido reemplaza al anterior,
+    /// salvo inject=, donde cada uno añade una regla.
+    pub fn parse(exprs: &[String]) -> anyhow::Result<Qualifiers> {
+        let mut q = Qualifiers::default();
+        for expr in exprs {
+            let (key, value) = expr.split_once('=').unwrap_or(("trace", expr));
+            match key {
+                "trace" | "t" => q.trace = TraceFilter::parse(value)?,
+                "status" => q.status = StatusFilter::parse(value)?,
+                "inject" => q.inject.push(InjectRule::parse(value)?),
+                _ => anyhow::bail!("calificador desconocido en -e: {key}"),
+            }
+        }
+        Ok(q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_nombres_y_negacion() {
+        let f = TraceFilter::parse("openat, read").unwrap();
+        assert!(f.matches(Abi::X86_64, "read") && f.matches(Abi::X86_64, "openat"));
+        assert!(!f.matches(Abi::X86_64, "write"));
+
+        let f = TraceFilter::parse("!read,write").unwrap();
+        assert!(!f.matches(Abi::X86_64, "read") && !f.matches(Abi::X86_64, "write"));
+        assert!(f.matches(Abi::X86_64, "openat"));
+    }
+
+    #[test]
+    fn trace_clases_y_regex() {
+        let f = TraceFilter::parse("%network").unwrap();
+        assert!(f.matches(Abi::X86_64, "connect") && f.matches(Abi::X86_64, "socketcall"));
+        assert!(!f.matches(Abi::X86_64, "read"));
+
+        let f = TraceFilter::parse("!%file").unwrap();
+        assert!(!f.matches(Abi::X86_64, "openat"));
+        assert!(f.matches(Abi::X86_64, "getpid"));
+
+        // La regex no está anclada salvo que se pida
+        let f = TraceFilter::parse("/^read").unwrap();
+        assert!(f.matches(Abi::X86_64, "read") && f.matches(Abi::X86_64, "readv"));
+        assert!(!f.matches(Abi::X86_64, "pread64"));
+    }
+
+    #[test]
+    fn trace_all_none() {
+        assert!(TraceFilter::parse("all").unwrap().matches(Abi::X86_64, "read"));
+        assert!(!TraceFilter::parse("none").unwrap().matches(Abi::X86_64, "read"));
+        assert!(TraceFilter::parse("!none").unwrap().matches(Abi::X86_64, "read"));
+        assert!(!TraceFilter::parse("!all").unwrap().matches(Abi::X86_64, "read"));
+    }
+
+    #[test]
+    fn trace_errores() {
+        assert!(TraceFilter::parse("no_existe").is_err());
+        assert!(TraceFilter::parse("%nada").is_err());
+        assert!(TraceFilter::parse("/(").is_err());
+    }
+
+    #[test]
+    fn status_conjuntos() {
+        let f = StatusFilter::parse("failed").unwrap();
+        assert!(f.matches(SyscallStatus::Failed));
+        assert!(!f.matches(SyscallStatus::Successful) && !f.matches(SyscallStatus::Unfinished));
+        assert!(!f.is_all());
+
+        let f = StatusFilter::parse("!failed").unwrap();
+        assert!(!f.matches(SyscallStatus::Failed));
+        assert!(f.matches(SyscallStatus::Successful) && f.matches(SyscallStatus::Unfinished));
+
+        assert!(StatusFilter::parse("successful,failed,unfinished").unwrap().is_all());
+        assert!(StatusFilter::parse("all").unwrap().is_all());
+        assert!(!StatusFilter::parse("none").unwrap().matches(SyscallStatus::Successful));
+        assert!(StatusFilter::parse("exitoso").is_err());
+
+        // -Z/-z se intersecan con -e status=
+        let failed = StatusFilter::parse("failed").unwrap();
+        let f = StatusFilter::parse("!unfinished").unwrap().intersect(failed);
+        assert!(f.matches(SyscallStatus::Failed) && !f.matches(SyscallStatus::Successful));
+        let f = StatusFilter::parse("unfinished").unwrap().intersect(failed);
+        assert!(!f.matches(SyscallStatus::Failed) && !f.matches(SyscallStatus::Unfinished));
+        assert_eq!(StatusFilter::default().intersect(failed), failed);
+    }
+
+    #[test]
+    fn qualifiers_en_orden() {
+        let exprs = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        // Sin calificador es trace=; uno repetido reemplaza al anterior
+        let q = Qualifiers::parse(&exprs(&["read", "trace=write"])).unwrap();
+        assert!(q.trace.matches(Abi::X86_64, "write") && !q.trace.matches(Abi::X86_64, "read"));
+
+        let q = Qualifiers::parse(&exprs(&["t=!read", "status=failed"])).unwrap();
+        assert!(!q.trace.matches(Abi::X86_64, "read") && q.trace.matches(Abi::X86_64, "write"));
+        assert!(!q.status.is_all());
+
+        // inject= acumula reglas
+        let q = Qualifiers::parse(&exprs(&["inject=read:error=EIO", "inject=write:retval=0"])).unwrap();
+        assert_eq!(q.inject.len(), 2);
+
+        assert!(Qualifiers::parse(&exprs(&["verbose=all"])).is_err());
+    }
+}
@@ -0,0 +1,438 @@
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::arch::{set_regs, set_syscall_number, Abi, Regs, SyscallRegs};
+use crate::filter::TraceFilter;
+use crate::sysdecode::{decode_errno, write_memory};
+
+// === Inyección en syscalls (-e inject=) ===
+// Cada -e inject=SET:ACCIÓN[:ACCIÓN...][:when=EXPR] define una regla: SET tiene la
+// sintaxis de trace= y las acciones dicen qué hacer con las syscalls elegidas.
+// error=ERRNO y retval=VAL hacen que la syscall no se ejecute (en la entrada se cambia
+// su número por uno inválido) y devuelva -ERRNO o VAL (en la salida se reescribe el
+// retorno). argN=VAL reescribe un argumento antes de ejecutarla (N desde 1) y
+// poke_exit=@argN[+OFF]=DATA escribe DATA en el buffer al que apunta el argumento N
+// si la syscall tuvo éxito (DATA admite escapes \xHH). delay_enter=USEC y
+// delay_exit=USEC retienen al tracee en el stop de entrada o de salida (sched.rs).
+// signal=SIG envía la señal al hilo en la entrada; el kernel la entrega al terminar la
+// syscall y llega por el camino normal de reinyección (Stopped en parent_trace).
+// when= elige qué invocaciones se alteran: N (solo la N-ésima), N..M, N+ (desde la
+// N-ésima), N+S / N..M+S (cada S). Se cuenta desde 1, por regla y por syscall, sumando
+// todos los tracees.
+
+/// Número de syscall inválido: el kernel la salta y devuelve -ENOSYS.
+pub const INVALID_SYSCALL: u64 = u64::MAX;
+
+/// Qué invocaciones altera una regla (when=).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct When {
+    first: u64,
+    last: Option<u64>, // None: sin límite
+    step: u64,
+}
+
+impl Default for When {
+    fn default() -> Self {
+        When { first: 1, last: None, step: 1 }
+    }
+}
+
+impl When {
+    fn parse(expr: &str) -> anyhow::Result<When> {
+        let num = |s: &str| -> anyhow::Result<u64> {
+            match s.trim().parse::<u64>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => anyhow::bail!("when= inválido: '{expr}' (se esperan números desde 1)"),
+            }
+        };
+        let (range, step) = match expr.split_once('+') {
+            Some((range, "")) => (range, Some(1)),
+            Some((range, step)) => (range, Some(num(step)?)),
+            None => (expr, None),
+        };
+        let when = match range.split_once("..") {
+            Some((first, last)) => When { first: num(first)?, last: Some(num(last)?), step: step.unwrap_or(1) },
+            // N sola: únicamente esa invocación; N+ o N+S: desde ahí sin límite
+            None if step.is_none() => When { first: num(range)?, last: Some(num(range)?), step: 1 },
+            None => When { first: num(range)?, last: None, step: step.unwrap_or(1) },
+        };
+        if when.last.is_some_and(|last| last < when.first) {
+            anyhow::bail!("when= inválido: '{expr}' (el final es menor que el inicio)");
+        }
+        Ok(when)
+    }
+
+    fn matches(&self, n: u64) -> bool {
+        n >= self.first && self.last.is_none_or(|last| n <= last) && (n - self.first) % self.step == 0
+    }
+}
+
+/// Una regla de -e inject=.
+#[derive(Debug)]
+pub struct InjectRule {
+    set: TraceFilter,
+    when: When,
+    error: Option<i32>,              // errno a devolver sin ejecutar la syscall
+    retval: Option<i64>,             // retorno a devolver sin ejecutar la syscall
+    args: Vec<(usize, u64)>,         // (índice desde 0, valor) a poner en la entrada
+    pokes: Vec<(usize, u64, Vec<u8>)>, // (argumento, desplazamiento, bytes) para la salida
+    pub delay_enter: Option<Duration>,
+    delay_exit: Option<Duration>,
+    pub signal: Option<Signal>, // señal a enviar al hilo al entrar en la syscall
+}
+
+/// Lo que queda pendiente de una inyección para el stop de salida.
+#[derive(Debug)]
+pub struct Injected {
+    ret: Option<i64>,
+    pokes: Vec<(u64, Vec<u8>)>, // (dirección, bytes)
+    pub delay_exit: Option<Duration>,
+    pub alters: bool, // la regla cambia la syscall (no solo la demora)
+}
+
+impl InjectRule {
+    pub fn parse(expr: &str) -> anyhow::Result<InjectRule> {
+        let mut parts = expr.split(':');
+        let set = TraceFilter::parse(parts.next().unwrap_or_default())?;
+        let mut rule = InjectRule {
+            set,
+            when: When::default(),
+            error: None,
+            retval: None,
+            args: Vec::new(),
+            pokes: Vec::new(),
+            delay_enter: None,
+            delay_exit: None,
+            signal: None,
+        };
+        for part in parts {
+            let (key, value) = part.split_once('=').unwrap_or((part, ""));
+            match key {
+                "error" => rule.error = Some(parse_errno(value)?),
+                "retval" => rule.retval = Some(parse_value(value)? as i64),
+                "when" => rule.when = When::parse(value)?,
+                "delay_enter" => rule.delay_enter = Some(parse_delay(value)?),
+                "delay_exit" => rule.delay_exit = Some(parse_delay(value)?),
+                "signal" => rule.signal = Some(parse_signal(value)?),
+                "poke_exit" => {
+                    let (target, data) =
+                        value.split_once('=').ok_or_else(|| anyhow::anyhow!("poke_exit= espera @argN[+OFF]=DATA"))?;
+                    let (arg, offset) = match target.split_once('+') {
+                        Some((arg, off)) => (arg, parse_value(off)?),
+                        None => (target, 0),
+                    };
+                    let arg = arg.strip_prefix('@').and_then(arg_index).ok_or_else(|| {
+                        anyhow::anyhow!("poke_exit= espera @arg1..@arg6, no '{target}'")
+                    })?;
+                    rule.pokes.push((arg, offset, parse_data(data)?));
+                }
+                _ => match arg_index(key) {
+                    Some(i) => rule.args.push((i, parse_value(value)?)),
+                    None => anyhow::bail!("acción desconocida en -e inject=: {key}"),
+                },
+            }
+        }
+        if rule.error.is_some() && rule.retval.is_some() {
+            anyhow::bail!("-e inject={expr}: error= y retval= son incompatibles");
+        }
+        if rule.error.is_none()
+            && rule.retval.is_none()
+            && rule.args.is_empty()
+            && rule.pokes.is_empty()
+            && rule.delay_enter.is_none()
+            && rule.delay_exit.is_none()
+            && rule.signal.is_none()
+        {
+            anyhow::bail!(
+                "-e inject={expr}: falta una acción (error=, retval=, argN=, poke_exit=, delay_enter=, delay_exit= o signal=)"
+            );
+        }
+        Ok(rule)
+    }
+
+    /// Aplica la regla en el stop de entrada: reescribe los argumentos (también en
+    /// `args`, para que la traza muestre los que verá el kernel) y, con error= o
+    /// retval=, anula la syscall. Devuelve lo pendiente para la salida.
+    pub fn enter(&self, pid: Pid, abi: Abi, regs: &mut Regs, args: &mut [u64; 6]) -> nix::Result<Injected> {
+        for &(i, value) in &self.args {
+            regs.set_arg(abi, i, value);
+            args[i] = value;
+        }
+        let ret = self.error.map(|errno| -(errno as i64)).or(self.retval);
+        if ret.is_some() {
+            set_syscall_number(pid, regs, INVALID_SYSCALL)?;
+        } else if !self.args.is_empty() {
+            set_regs(pid, regs)?;
+        }
+        let pokes = self.pokes.iter().map(|(i, off, data)| (args[*i].wrapping_add(*off), data.clone())).collect();
+        Ok(Injected { ret, pokes, delay_exit: self.delay_exit, alters: self.alters() })
+    }
+
+    /// ¿Altera la regla la syscall? (las demoras no cuentan como inyección)
+    pub fn alters(&self) -> bool {
+        self.error.is_some() || self.retval.is_some() || !self.args.is_empty() || !self.pokes.is_empty()
+    }
+}
+
+impl Injected {
+    /// ¿Se anuló la syscall en la entrada? (error= o retval=: el retorno se pone en la salida)
+    pub fn nullifies(&self) -> bool {
+        self.ret.is_some()
+    }
+
+    /// Completa la inyección en el stop de salida: escribe los buffers (si la syscall
+    /// tuvo éxito) y reescribe el retorno. `ret` queda con el que verá el tracee.
+    pub fn exit(&self, pid: Pid, mut regs: Regs, ret: &mut i64) -> nix::Result<()> {
+        if let Some(value) = self.ret {
+            regs.set_return_value(value);
+            set_regs(pid, &regs)?;
+            *ret = value;
+        }
+        if *ret >= 0 {
+            for (addr, data) in &self.pokes {
+                write_memory(pid, *addr, data).map_err(|_| nix::errno::Errno::EFAULT)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Señal por nombre (SIGUSR1 o USR1) o número.
+fn parse_signal(value: &str) -> anyhow::Result<Signal> {
+    let sig = match value.parse::<i32>() {
+        Ok(n) => Signal::try_from(n).ok(),
+        Err(_) if value.starts_with("SIG") => value.parse().ok(),
+        Err(_) => format!("SIG{value}").parse().ok(),
+    };
+    sig.ok_or_else(|| anyhow::anyhow!("señal desconocida en -e inject=: {value}"))
+}
+
+/// Envía `sig` solo al hilo `tid` del proceso `tgid` (tgkill, no kill: con hilos la
+/// señal debe llegar al que hizo la syscall).
+pub fn send_signal(tgid: i32, tid: Pid, sig: Signal) -> nix::Result<()> {
+    // SAFETY: tgkill solo recibe enteros
+    let res = unsafe { libc::syscall(libc::SYS_tgkill, tgid, tid.as_raw(), sig as libc::c_int) };
+    nix::errno::Errno::result(res).map(drop)
+}
+
+/// Señal de signal= enviada a un hilo y aún no vista en su stop de entrega. El kernel
+/// la entrega al terminar la syscall en que se envió, antes de la siguiente entrada:
+/// si no llegó para entonces quedó bloqueada (o se descartó), y una entrega posterior
+/// con el mismo número ya no se atribuye a la inyección.
+#[derive(Debug, Default)]
+pub struct PendingSignal(Option<Signal>);
+
+impl PendingSignal {
+    /// Entrada de una syscall del hilo: olvida la señal que no se entregó.
+    pub fn syscall_entry(&mut self) {
+        self.0 = None;
+    }
+
+    pub fn sent(&mut self, sig: Signal) {
+        self.0 = Some(sig);
+    }
+
+    /// ¿Es `sig` la señal inyectada? Si lo es, se da por entregada.
+    pub fn delivered(&mut self, sig: Signal) -> bool {
+        let hit = self.0 == Some(sig);
+        if hit {
+            self.0 = None;
+        }
+        hit
+    }
+}
+
+/// `arg1`..`arg6` → índice desde 0.
+fn arg_index(key: &str) -> Option<usize> {
+    key.strip_prefix("arg")?.parse::<usize>().ok().filter(|n| (1..=6).contains(n)).map(|n| n - 1)
+}
+
+/// Valor numérico: decimal (con signo) o hexadecimal 0x.
+pub fn parse_value(value: &str) -> anyhow::Result<u64> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse::<i64>().ok().map(|v| v as u64),
+    };
+    parsed.ok_or_else(|| anyhow::anyhow!("valor inválido en -e inject=: {value}"))
+}
+
+/// Demora de delay_enter=/delay_exit= en microsegundos: sin signo (un `-1` convertido a
+/// u64 retendría al tracee para siempre).
+fn parse_delay(value: &str) -> anyhow::Result<Duration> {
+    value
+        .parse::<u64>()
+        .map(Duration::from_micros)
+        .map_err(|_| anyhow::anyhow!("demora inválida en -e inject=: {value} (microsegundos, sin signo)"))
+}
+
+/// Bytes de poke_exit=: texto literal con escapes \xHH y \\.
+fn parse_data(data: &str) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut rest = data.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        if b != b'\\' {
+            bytes.push(b);
+            continue;
+        }
+        match rest {
+            [b'x', h, l, tail @ ..] => {
+                let hex = std::str::from_utf8(&[*h, *l]).ok().and_then(|s| u8::from_str_radix(s, 16).ok());
+                bytes.push(hex.ok_or_else(|| anyhow::anyhow!("escape inválido en poke_exit=: {data}"))?);
+                rest = tail;
+            }
+            [b'\\', tail @ ..] => {
+                bytes.push(b'\\');
+                rest = tail;
+            }
+            _ => anyhow::bail!("escape inválido en poke_exit=: {data}"),
+        }
+    }
+    Ok(bytes)
+}
+
+/// ERRNO por nombre (ENOSPC) o número (28).
+fn parse_errno(value: &str) -> anyhow::Result<i32> {
+    if let Ok(n) = value.parse::<i32>() {
+        if (1..4096).contains(&n) {
+            return Ok(n);
+        }
+    }
+    (1..4096)
+        .find(|&n| decode_errno(n) == value)
+        .ok_or_else(|| anyhow::anyhow!("errno desconocido en -e inject=: {value}"))
+}
+
+/// Contadores de invocaciones para when=.
+#[derive(Debug, Default)]
+pub struct Injector {
+    counts: HashMap<(usize, String), u64>, // (regla, syscall) → invocaciones vistas
+}
+
+impl Injector {
+    /// Regla que toca aplicar a esta invocación de `name`, si alguna. Cuenta solo la
+    /// primera regla cuyo SET contiene la syscall (las siguientes no la ven).
+    pub fn select<'a>(&mut self, rules: &'a [InjectRule], abi: Abi, name: &str) -> Option<&'a InjectRule> {
+        let (i, rule) = rules.iter().enumerate().find(|(_, r)| r.set.matches(abi, name))?;
+        let n = self.counts.entry((i, name.to_string())).or_insert(0);
+        *n += 1;
+        rule.when.matches(*n).then_some(rule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hits(expr: &str, upto: u64) -> Vec<u64> {
+        let when = When::parse(expr).unwrap();
+        (1..=upto).filter(|&n| when.matches(n)).collect()
+    }
+
+    #[test]
+    fn when_rangos() {
+        assert_eq!(hits("3", 10), [3]);
+        assert_eq!(hits("2..4", 10), [2, 3, 4]);
+        assert_eq!(hits("8+", 10), [8, 9, 10]);
+        assert_eq!(hits("2+3", 10), [2, 5, 8]);
+        assert_eq!(hits("1..7+2", 10), [1, 3, 5, 7]);
+        assert_eq!(hits("4..4", 10), [4]);
+        assert_eq!(When::parse("1+").unwrap(), When::default());
+    }
+
+    #[test]
+    fn when_invalidos() {
+        for expr in ["", "0", "0..3", "5..2", "2+0", "a", "1..", "..3", "-1"] {
+            assert!(When::parse(expr).is_err(), "when={expr} debería fallar");
+        }
+    }
+
+    #[test]
+    fn datos_de_poke() {
+        assert_eq!(parse_data("hola").unwrap(), b"hola");
+        assert_eq!(parse_data(r"a\x00\xffb").unwrap(), b"a\x00\xffb");
+        assert_eq!(parse_data(r"\\x41").unwrap(), br"\x41");
+        assert_eq!(parse_data(r"\x4A\x4a").unwrap(), b"JJ");
+        assert!(parse_data(r"\x4").is_err());
+        assert!(parse_data(r"\xzz").is_err());
+        assert!(parse_data(r"\n").is_err());
+        assert!(parse_data("\\").is_err());
+    }
+
+    #[test]
+    fn valores_numericos() {
+        assert_eq!(parse_value("42").unwrap(), 42);
+        assert_eq!(parse_value("0x1f").unwrap(), 0x1f);
+        assert_eq!(parse_value("-1").unwrap(), u64::MAX);
+        assert!(parse_value("0xg").is_err());
+        assert!(parse_value("").is_err());
+
+        assert_eq!(parse_delay("1500").unwrap(), Duration::from_micros(1500));
+        assert!(parse_delay("-1").is_err());
+        assert!(parse_delay("0x10").is_err());
+    }
+
+    #[test]
+    fn errnos_y_senales() {
+        assert_eq!(parse_errno("ENOENT").unwrap(), libc::ENOENT);
+        assert_eq!(parse_errno("28").unwrap(), libc::ENOSPC);
+        assert!(parse_errno("0").is_err());
+        assert!(parse_errno("ENOPE").is_err());
+
+        assert_eq!(parse_signal("SIGUSR1").unwrap(), Signal::SIGUSR1);
+        assert_eq!(parse_signal("USR2").unwrap(), Signal::SIGUSR2);
+        assert_eq!(parse_signal("9").unwrap(), Signal::SIGKILL);
+        assert!(parse_signal("SIGNADA").is_err());
+    }
+
+    #[test]
+    fn senal_pendiente() {
+        let mut pending = PendingSignal::default();
+        pending.sent(Signal::SIGUSR1);
+        assert!(!pending.delivered(Signal::SIGCHLD));
+        assert!(pending.delivered(Signal::SIGUSR1));
+        assert!(!pending.delivered(Signal::SIGUSR1));
+
+        // Bloqueada: no llegó antes de la siguiente syscall, así que una entrega
+        // posterior del mismo número es natural
+        pending.sent(Signal::SIGUSR1);
+        pending.syscall_entry();
+        assert!(!pending.delivered(Signal::SIGUSR1));
+    }
+
+    #[test]
+    fn reglas() {
+        let rule = InjectRule::parse("read,write:error=EIO:when=2+").unwrap();
+        assert_eq!(rule.error, Some(libc::EIO));
+        assert!(rule.set.matches(Abi::X86_64, "write") && !rule.set.matches(Abi::X86_64, "openat"));
+        assert_eq!(rule.when, When { first: 2, last: None, step: 1 });
+
+        let rule = InjectRule::parse(r"uname:poke_exit=@arg1+65=vm\x00:arg2=7").unwrap();
+        assert_eq!(rule.pokes, [(0, 65, b"vm\x00".to_vec())]);
+        assert_eq!(rule.args, [(1, 7)]);
+        assert!(rule.alters());
+
+        // Solo una demora no altera la syscall
+        assert!(!InjectRule::parse("read:delay_exit=10").unwrap().alters());
+
+        assert!(InjectRule::parse("read").is_err());
+        assert!(InjectRule::parse("read:error=EIO:retval=0").is_err());
+        assert!(InjectRule::parse("read:arg7=1").is_err());
+        assert!(InjectRule::parse("read:poke_exit=@arg0=x").is_err());
+        assert!(InjectRule::parse("read:frobnicate=1").is_err());
+    }
+
+    #[test]
+    fn contadores_por_regla_y_syscall() {
+        let rules = [InjectRule::parse("read:error=EIO:when=2").unwrap(), InjectRule::parse("all:retval=0:when=1").unwrap()];
+        let mut inj = Injector::default();
+        let picks: Vec<Option<i32>> = ["read", "read", "write", "write", "read"]
+            .iter()
+            .map(|name| inj.select(&rules, Abi::X86_64, name).map(|r| r.error.unwrap_or(0)))
+            .collect();
+        // read: la 2.ª invocación de la regla 0; write: solo la 1.ª de la regla 1
+        assert_eq!(picks, [None, Some(libc::EIO), Some(0), None, None]);
+    }
+}
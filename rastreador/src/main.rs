@@ -1,23 +1,42 @@
-use clap::{ArgAction, Parser};
-use crossterm::event::{read, Event};
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
-use libc;
+use clap::{ArgAction, Parser, ValueEnum};
+use crossterm::terminal::{disable_raw_mode, is_raw_mode_enabled};
 use nix::sys::ptrace;
+use nix::sys::signal::Signal;
 use nix::unistd::{execvp, fork, ForkResult, Pid};
-use once_cell::sync::Lazy;
 use std::collections::HashMap;
 use std::ffi::CString;
-use std::io;
-use std::io::Write;
-use std::mem::size_of;
-use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
-use anyhow;
+use std::time::{Duration, Instant};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use nix::errno::Errno;
-use nix::sys::wait::{waitpid, WaitStatus};
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet};
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
 
+mod arch; // abstracción de registros y ABIs (x86_64, i386 compat, aarch64)
+mod debugger; // prompt interactivo de -V (paso a paso, breakpoints, memoria, registros)
+mod event; // SyscallEvent: una syscall completa, compartida por los formatos de salida
+mod fdtable; // modelo de la tabla de fds por proceso y reporte de fugas (--fd-report)
+mod filter; // expresiones de calificación de -e (trace=...)
+mod inject; // reglas de -e inject= y contadores de when=
+mod output; // destino de la traza: stderr, -o FILE o un archivo por proceso
+mod sched; // retención de tracees con plazo para delay_enter/delay_exit
+mod summary; // estadísticas por syscall y tabla de resumen final
+mod syscall_info; // PTRACE_GET_SYSCALL_INFO: entrada/salida autoritativa
 mod sysdecode; // helpers para nombres de syscalls y lectura de memoria del hijo
-use sysdecode::{decode_errno, fmt_flags_open, read_c_string, read_ptr, syscall_name};
+use arch::{get_regs, Abi, SyscallRegs};
+use debugger::{Action, Debugger, Stop};
+use event::{fmt_duration, fmt_timestamp, SyscallEvent, SyscallStatus};
+use fdtable::{print_fd_report, print_fd_report_json, FdTables};
+use inject::{send_signal, Injected, Injector, PendingSignal};
+use filter::{PathFilter, Qualifiers, StatusFilter};
+use output::{tgid_of, Output};
+use sched::Scheduler;
+use serde_json::json;
+use summary::{
+    io_fds, print_io_report, print_io_report_json, print_summary, print_summary_json, HistogramBy, IoDir, SortBy, Stats,
+};
+use syscall_info::{get_syscall_info, SyscallStop};
+use sysdecode::{fd_arg, fd_path, syscall_name, FdCache};
 
 #[derive(Parser, Debug)]
 #[command(name = "rastreador", about = "Tracer de syscalls estilo strace (simplificado)")]
@@ -30,27 +49,235 @@ struct Opts {
     #[arg(short = 'V', long = "very", action = ArgAction::SetTrue)]
     very_verbose: bool,
 
-    /// Programa objetivo a ejecutar (Prog)
-    #[arg(required = true)]
-    prog: String,
+    /// Muestra el puntero de instrucción y de pila en cada syscall (con -v)
+    #[arg(short = 'i', long = "instruction-pointer", action = ArgAction::SetTrue)]
+    instruction_pointer: bool,
 
-    /// Argumentos de Prog (se pasan tal cual)
-    #[arg(trailing_var_arg = true)]
-    args: Vec<String>,
+    /// Muestra el destino de cada fd: `3</etc/passwd>`, `5<TCP:127.0.0.1:8080->127.0.0.1:4321>` (con -v)
+    #[arg(short = 'y', long = "decode-fds", action = ArgAction::SetTrue)]
+    decode_fds: bool,
+
+    /// Tras el resumen, tabla de bytes leídos/escritos, llamadas y tiempo por archivo o socket
+    #[arg(long = "io-report", action = ArgAction::SetTrue)]
+    io_report: bool,
+
+    /// Al terminar, reporta fds sin cerrar, filtrados en execve sin O_CLOEXEC y close dobles/EBADF
+    #[arg(long = "fd-report", action = ArgAction::SetTrue)]
+    fd_report: bool,
+
+    /// Marca de tiempo absoluta en cada línea: -t (HH:MM:SS), -tt (con µs), -ttt (epoch con µs)
+    #[arg(short = 't', action = ArgAction::Count)]
+    timestamps: u8,
+
+    /// Marca de tiempo relativa: tiempo desde la entrada de la syscall anterior
+    #[arg(short = 'r', long = "relative-timestamps", action = ArgAction::SetTrue)]
+    relative: bool,
+
+    /// Muestra el tiempo pasado en cada syscall (del stop de entrada al de salida)
+    #[arg(short = 'T', long = "syscall-times", action = ArgAction::SetTrue)]
+    syscall_times: bool,
+
+    /// Solo imprime el resumen final, sin la traza de cada syscall (anula -v/-V)
+    #[arg(short = 'c', long = "summary-only", action = ArgAction::SetTrue)]
+    summary_only: bool,
+
+    /// Orden de la tabla de resumen
+    #[arg(long = "summary-sort-by", value_enum, value_name = "CRITERIO", default_value_t = SortBy::Calls)]
+    summary_sort_by: SortBy,
+
+    /// Histogramas log2 de latencia por syscall (con =path, también por ruta del fd) tras el
+    /// resumen, que suma las columnas p50/p90/p99
+    #[arg(long = "histogram", value_enum, value_name = "POR", num_args = 0..=1, require_equals = true,
+          default_missing_value = "syscall")]
+    histogram: Option<HistogramBy>,
+
+    /// Expresión de calificación (repetible): trace=LISTA con nombres, !negación, /regex y
+    /// clases %file, %network, %process, %signal, %memory, %ipc, %desc
+    #[arg(short = 'e', value_name = "EXPR", action = ArgAction::Append)]
+    exprs: Vec<String>,
+
+    /// Solo muestra las syscalls que fallaron (como -e status=failed)
+    #[arg(short = 'Z', long = "failed-only", conflicts_with = "successful_only", action = ArgAction::SetTrue)]
+    failed_only: bool,
+
+    /// Solo muestra las syscalls que tuvieron éxito (como -e status=successful)
+    #[arg(short = 'z', long = "successful-only", action = ArgAction::SetTrue)]
+    successful_only: bool,
+
+    /// Solo muestra las syscalls que se refieren a PATH (por argumento ruta o por fd); repetible
+    #[arg(short = 'P', long = "trace-path", value_name = "PATH", action = ArgAction::Append)]
+    trace_paths: Vec<String>,
+
+    /// Calificadores de -e ya interpretados
+    #[arg(skip)]
+    qual: Qualifiers,
+
+    /// Sigue forks, vforks y clones (traza todo el árbol de procesos/hilos)
+    #[arg(short = 'f', long = "follow-forks", action = ArgAction::SetTrue)]
+    follow_forks: bool,
+
+    /// Adjunta a un proceso ya en ejecución (y a todos sus hilos); repetible
+    #[arg(short = 'p', long = "attach", value_name = "PID", action = ArgAction::Append)]
+    pids: Vec<i32>,
+
+    /// Escribe la traza y el resumen en FILE en lugar de stderr/stdout
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    output: Option<String>,
+
+    /// Con -o: un archivo FILE.<pid> por proceso trazado (el resumen queda en FILE)
+    #[arg(long = "output-separately", requires = "output", action = ArgAction::SetTrue)]
+    output_separately: bool,
+
+    /// Formato de la traza: text (líneas →/←) o jsonl (un objeto JSON por syscall; implica -v)
+    #[arg(long = "format", value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Salida compatible con strace: una línea `name(args) = ret` por syscall, con
+    /// `<unfinished ...>`/`<... resumed>` y el escapado de cadenas de strace (implica -v)
+    #[arg(long = "strace-compat", conflicts_with = "format", action = ArgAction::SetTrue)]
+    strace_compat: bool,
+
+    /// Programa objetivo a ejecutar (Prog) y sus argumentos. Todo lo que sigue a Prog se
+    /// le pasa tal cual, aunque coincida con una opción del rastreador (`sh -c 'echo hi'`)
+    #[arg(value_name = "PROG [ARGS]", required_unless_present = "pids", conflicts_with = "pids",
+          trailing_var_arg = true)]
+    command: Vec<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Text,
+    Jsonl,
+    #[value(skip)]
+    Strace, // --strace-compat
 }
 
 fn main() -> anyhow::Result<()> {
     let mut opts = Opts::parse();
-    if opts.very_verbose {
-        opts.verbose = true; // -V implica -v
+    if opts.strace_compat {
+        opts.format = Format::Strace;
+        sysdecode::set_strace_quoting(true);
+    }
+    if opts.very_verbose || opts.format != Format::Text {
+        opts.verbose = true; // -V, --format=jsonl y --strace-compat implican -v
+    }
+    opts.qual = Qualifiers::parse(&opts.exprs)?;
+    opts.qual.paths = PathFilter::new(&opts.trace_paths);
+    // -Z/-z restringen lo que ya pida -e status= en lugar de reemplazarlo
+    if opts.failed_only {
+        opts.qual.status = opts.qual.status.intersect(StatusFilter::parse("failed")?);
+    } else if opts.successful_only {
+        opts.qual.status = opts.qual.status.intersect(StatusFilter::parse("successful")?);
+    }
+    if opts.summary_only {
+        opts.verbose = false;
+        opts.very_verbose = false;
+    }
+
+    // El archivo de -o se crea antes de lanzar Prog para fallar pronto (es O_CLOEXEC:
+    // el tracee no lo hereda)
+    let out = Output::open(opts.output.as_deref(), opts.output_separately)
+        .map_err(|e| anyhow::anyhow!("no se pudo crear {}: {e}", opts.output.as_deref().unwrap_or_default()))?;
+
+    // -p: adjunta a procesos existentes en lugar de lanzar Prog
+    if !opts.pids.is_empty() {
+        let tids = attach_all(&opts)?;
+        install_signal_handlers()?;
+        parent_trace(Target::Attached(tids), &opts, out);
     }
-    ensure_prog_exists(&opts.prog)?;
+
+    let prog = opts.command.first().map(String::as_str).unwrap_or_default();
+    ensure_prog_exists(prog)?;
 
     // fork + ptrace
     match unsafe { fork()? } {
         ForkResult::Child => child_exec(&opts),
-        ForkResult::Parent { child } => parent_trace(child, &opts),
+        ForkResult::Parent { child } => {
+            install_signal_handlers()?;
+            parent_trace(Target::Launched(child), &opts, out)
+        }
+    }
+}
+
+/// Opciones de ptrace comunes a ambos modos (lanzado y adjunto).
+fn trace_options(opts: &Opts) -> ptrace::Options {
+    // TRACESYSGOOD distingue stops de syscalls y TRACEEXEC hace que el SIGTRAP posterior
+    // a cada execve llegue como evento (y no se reinyecte al hijo); con -f además
+    // seguimos forks/hilos
+    let mut options = ptrace::Options::PTRACE_O_TRACESYSGOOD | ptrace::Options::PTRACE_O_TRACEEXEC;
+    if opts.follow_forks {
+        options |= ptrace::Options::PTRACE_O_TRACEFORK
+            | ptrace::Options::PTRACE_O_TRACEVFORK
+            | ptrace::Options::PTRACE_O_TRACECLONE;
     }
+    options
+}
+
+/// Adjunta (PTRACE_SEIZE + PTRACE_INTERRUPT) a cada PID de -p y a todos sus hilos
+/// según /proc/PID/task. Se relee el directorio hasta que no aparezcan hilos nuevos,
+/// por si el proceso crea hilos mientras nos adjuntamos.
+fn attach_all(opts: &Opts) -> anyhow::Result<Vec<Pid>> {
+    let options = trace_options(opts);
+    let mut attached: Vec<Pid> = Vec::new();
+    for &pid in &opts.pids {
+        let task_dir = format!("/proc/{pid}/task");
+        loop {
+            let entries = std::fs::read_dir(&task_dir)
+                .map_err(|e| anyhow::anyhow!("No se pudo leer {task_dir}: {e}"))?;
+            let mut new_tids = 0;
+            for entry in entries.flatten() {
+                let Some(tid) = entry.file_name().to_str().and_then(|t| t.parse::<i32>().ok()) else {
+                    continue;
+                };
+                let tid = Pid::from_raw(tid);
+                if attached.contains(&tid) {
+                    continue;
+                }
+                match ptrace::seize(tid, options) {
+                    Ok(()) => {}
+                    Err(Errno::ESRCH) => continue, // el hilo terminó entre read_dir y seize
+                    Err(e) => anyhow::bail!("No se pudo adjuntar al proceso {tid}: {e}"),
+                }
+                ptrace::interrupt(tid).map_err(|e| anyhow::anyhow!("ptrace(INTERRUPT) {tid}: {e}"))?;
+                attached.push(tid);
+                new_tids += 1;
+            }
+            if new_tids == 0 {
+                break;
+            }
+        }
+        eprintln!("[rastreador] Adjunto al proceso {pid}");
+    }
+    Ok(attached)
+}
+
+// SIGINT/SIGTERM recibida por el rastreador: el handler solo la anota aquí y el bucle
+// principal decide (desadjuntar o reenviar al hijo). 0 = ninguna pendiente.
+pub(crate) static PENDING_SIGNAL: AtomicI32 = AtomicI32::new(0);
+// true si la señal vino de la terminal (Ctrl-C): el hijo lanzado, que comparte grupo
+// de procesos en primer plano, ya la recibió y no hay que reenviársela.
+pub(crate) static SIGNAL_FROM_TTY: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_signal(sig: libc::c_int, info: *mut libc::siginfo_t, _ctx: *mut libc::c_void) {
+    let from_tty = !info.is_null() && unsafe { (*info).si_code } == libc::SI_KERNEL;
+    SIGNAL_FROM_TTY.store(from_tty, Ordering::SeqCst);
+    PENDING_SIGNAL.store(sig, Ordering::SeqCst);
+}
+
+fn install_signal_handlers() -> anyhow::Result<()> {
+    // Sin SA_RESTART: así waitpid retorna EINTR y el bucle revisa la señal pendiente
+    let action = SigAction::new(SigHandler::SigAction(on_signal), SaFlags::SA_SIGINFO, SigSet::empty());
+    for sig in [Signal::SIGINT, Signal::SIGTERM] {
+        unsafe { sigaction(sig, &action)? };
+    }
+    Ok(())
+}
+
+/// Toma (y limpia) la señal pendiente, indicando si provino de la terminal.
+fn take_pending_signal() -> Option<(Signal, bool)> {
+    let signo = PENDING_SIGNAL.swap(0, Ordering::SeqCst);
+    let sig = Signal::try_from(signo).ok()?;
+    Some((sig, SIGNAL_FROM_TTY.load(Ordering::SeqCst)))
 }
 
 fn ensure_prog_exists(p: &str) -> anyhow::Result<()> {
@@ -69,12 +296,8 @@ fn child_exec(opts: &Opts) -> ! {
     ptrace::traceme().expect("ptrace(TRACEME) falló");
 
     // Prepara argv para execvp
-    let prog_c = CString::new(opts.prog.as_str()).unwrap();
-    let mut argv: Vec<CString> = Vec::with_capacity(1 + opts.args.len());
-    argv.push(prog_c.clone());
-    for a in &opts.args {
-        argv.push(CString::new(a.as_str()).unwrap());
-    }
+    let argv: Vec<CString> = opts.command.iter().map(|a| CString::new(a.as_str()).unwrap()).collect();
+    let prog_c = argv[0].clone();
 
     // Ejecuta (si falla, imprime y sale)
     match execvp(&prog_c, &argv) {
@@ -86,100 +309,593 @@ fn child_exec(opts: &Opts) -> ! {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 struct ThreadState {
-    entering: bool,           // alterna entre entrada/salida de syscall
+    entering: bool,           // alterna entre entrada/salida de syscall (respaldo sin syscall info)
+    in_syscall: bool,         // se vio la entrada de la syscall en curso
     last_syscall: u64,        // número de syscall en entrada
+    args: [u64; 6],           // argumentos de la entrada (para --fd-report en la salida)
+    event: Option<SyscallEvent>, // syscall en curso con -v (se completa y emite en la salida)
+    tgid: Option<i32>,        // TGID del hilo (cacheado: /proc desaparece al salir)
+    traced: bool,             // la syscall en curso pasa el filtro de -e trace=
+    entry_time: Option<Instant>, // instante en que siguió tras la entrada (para -T y los tiempos del resumen)
+    fd_path: Option<String>,  // con --histogram=path: ruta del fd de la syscall en curso
+    io_targets: Vec<(IoDir, String)>, // con --io-report: destinos de los fds de E/S en curso
+    injected: Option<Injected>, // -e inject=: lo que falta aplicar en la salida de la syscall en curso
+    injected_signal: PendingSignal, // -e inject=signal: enviada y aún no entregada
+    abi: Abi,                 // ABI de la syscall en curso (x86_64, i386, aarch64)
+    pending_sigstop: bool,    // tracee nuevo: su SIGSTOP inicial no debe reinyectarse
 }
 
-fn parent_trace(child: Pid, opts: &Opts) -> ! {
-    // Espera el primer stop (por exec/Señal)
-    wait_for_any_stop(child);
+impl Default for ThreadState {
+    fn default() -> Self {
+        // Al primer PtraceSyscall de cada TID el stop es de entrada
+        ThreadState {
+            entering: true,
+            in_syscall: false,
+            last_syscall: 0,
+            args: [0; 6],
+            event: None,
+            tgid: None,
+            traced: true,
+            entry_time: None,
+            fd_path: None,
+            io_targets: Vec::new(),
+            injected: None,
+            injected_signal: PendingSignal::default(),
+            abi: Abi::native(),
+            pending_sigstop: false,
+        }
+    }
+}
 
-    // Habilita TRACESYSGOOD para distinguir stops de syscalls
-    let options = ptrace::Options::PTRACE_O_TRACESYSGOOD;
-    ptrace::setoptions(child, options).expect("ptrace(SETOPTIONS)");
+/// Qué se está trazando: un Prog lanzado por nosotros (TRACEME) o procesos
+/// existentes adjuntados con -p (SEIZE).
+enum Target {
+    Launched(Pid),
+    Attached(Vec<Pid>),
+}
 
-    // Estado por TID (aunque no seguimos forks/hilos, ser robusto no estorba)
+fn parent_trace(target: Target, opts: &Opts, mut out: Output) -> ! {
+    // Estado por TID: con -f cada proceso/hilo nuevo recibe el suyo
     let mut per_tid: HashMap<Pid, ThreadState> = HashMap::new();
-    let mut counts: HashMap<u64, u64> = HashMap::new();
-    let mut total_calls: u64 = 0;
+    let mut stats = Stats::new(opts.histogram);
+    let mut fds = FdCache::default(); // destinos de fds para -y, por proceso
+    let mut fdt = FdTables::default(); // modelo de las tablas de fds para --fd-report
+    let mut injector = Injector::default(); // invocaciones contadas para when= de -e inject=
+    let mut sched = Scheduler::default(); // tracees retenidos por delay_enter/delay_exit
+    let mut debugger = Debugger::default(); // estado del prompt de -V
+    let mut root_status: i32 = 0; // código de salida a propagar (el del hijo raíz)
+    let mut last_entry: Option<Instant> = None; // entrada de la syscall anterior (para -r)
+
+    let child = match &target {
+        Target::Launched(child) => {
+            let child = *child;
+            // Espera el primer stop (por exec/Señal)
+            wait_for_any_stop(child);
+            ptrace::setoptions(child, trace_options(opts)).expect("ptrace(SETOPTIONS)");
+            per_tid.insert(child, ThreadState { tgid: Some(child.as_raw()), ..Default::default() });
 
-    // Arranca el bucle
-    ptrace::syscall(child, None).expect("ptrace(SYSCALL) inicial");
+            // Arranca el bucle
+            ptrace::syscall(child, None).expect("ptrace(SYSCALL) inicial");
+            Some(child)
+        }
+        Target::Attached(tids) => {
+            // Cada hilo reportará su PTRACE_EVENT_STOP (por INTERRUPT) dentro del bucle
+            for &tid in tids {
+                per_tid.insert(tid, ThreadState { tgid: Some(tgid_of(tid)), ..Default::default() });
+            }
+            None
+        }
+    };
+    let is_root = |pid: Pid| child == Some(pid) && !opts.follow_forks;
+    let mut forwarded = false; // ya se reenvió una señal al hijo lanzado
 
     loop {
-        match waitpid(None, None) {
+        if let Some((sig, from_tty)) = take_pending_signal() {
+            match child {
+                // Adjunto (-p): soltar los procesos y dejarlos correr
+                None => {
+                    for pid in sched.release_all() {
+                        resume(pid, None);
+                    }
+                    let detached = detach_all(&per_tid);
+                    eprintln!("[rastreador] Desadjuntado de {} tracee(s)", detached);
+                    finish(&mut out, &stats, &fdt, 0, opts);
+                }
+                // Lanzado: la primera señal se reenvía al hijo y se sigue trazando hasta que
+                // termine (el resumen sale por la vía normal). Si el hijo la ignora (p. ej. el
+                // `trap ... INT` de prog.sh), una segunda señal corta el trazado.
+                Some(child) if !forwarded => {
+                    forwarded = true;
+                    if !from_tty {
+                        let _ = nix::sys::signal::kill(child, sig);
+                    }
+                    eprintln!("[rastreador] {sig:?} reenviada a {child}; repita para terminar el rastreo");
+                }
+                Some(_) => {
+                    eprintln!("[rastreador] Rastreo interrumpido por {sig:?}");
+                    finish(&mut out, &stats, &fdt, 128 + sig as i32, opts);
+                }
+            }
+        }
+
+        // Los retenidos cuyo plazo venció siguen su camino
+        for pid in sched.due() {
+            resume_syscall(&mut per_tid, pid);
+        }
+
+        // __WALL: necesario para recibir también los stops de hilos (clone). Con tracees
+        // retenidos la espera vuelve también al vencer el primer plazo (StillAlive)
+        match sched.waitpid() {
+            Ok(WaitStatus::StillAlive) => continue,
             Ok(WaitStatus::Exited(pid, status)) => {
-                // Proceso principal terminó
-                if pid == child {
-                    print_summary(&counts, total_calls);
-                    std::process::exit(status);
+                let st = thread_exit(&mut per_tid, &mut fds, &mut fdt, pid);
+                log_vanished(&mut out, pid, st, opts);
+                // strace siempre informa la salida; el formato propio solo con -f
+                if opts.verbose && (opts.follow_forks || opts.format == Format::Strace) {
+                    log_process(&mut out, pid, opts, ProcEvent::Exited(status));
+                }
+                out.forget(pid);
+                if child == Some(pid) {
+                    root_status = status;
+                }
+                // Sin -f termina con el hijo raíz; con -f (o -p), cuando no quedan tracees
+                if is_root(pid) || per_tid.is_empty() {
+                    finish(&mut out, &stats, &fdt, root_status, opts);
                 }
             }
             Ok(WaitStatus::Signaled(pid, sig, _core)) => {
-                if pid == child {
+                let st = thread_exit(&mut per_tid, &mut fds, &mut fdt, pid);
+                log_vanished(&mut out, pid, st, opts);
+                if opts.verbose && (child != Some(pid) || opts.format == Format::Strace) {
+                    log_process(&mut out, pid, opts, ProcEvent::Killed(sig));
+                }
+                out.forget(pid);
+                if child == Some(pid) {
                     eprintln!("[rastreador] Proceso terminó por señal {sig:?}");
-                    print_summary(&counts, total_calls);
-                    std::process::exit(128 + sig as i32);
+                    root_status = 128 + sig as i32;
+                }
+                if is_root(pid) || per_tid.is_empty() {
+                    finish(&mut out, &stats, &fdt, root_status, opts);
                 }
             }
             Ok(WaitStatus::PtraceSyscall(pid)) => {
                 // Stop por entrada o salida de syscall
-                let st = per_tid.entry(pid).or_insert_with(|| ThreadState { entering: true, last_syscall: 0 });
-                let regs = ptrace_getregs(pid).expect("GETREGS");
+                let st = per_tid.entry(pid).or_default();
+                let regs = match get_regs(pid) {
+                    Ok(r) => r,
+                    Err(_) => continue, // el tracee desapareció (p. ej. exit_group de otro hilo)
+                };
 
-                if st.entering {
-                    // ENTRADA: registra número y muestra args si -v
-                    let scno = regs.orig_rax as u64; // número está en ORIG_RAX al entrar
+                // Entrada o salida: lo dice PTRACE_GET_SYSCALL_INFO; el toggle `entering` solo
+                // se usa como respaldo en kernels sin soporte (< 5.3)
+                let info = get_syscall_info(pid);
+                let mut delay = None; // -e inject=delay_*: retener al tracee en este stop
+                let mut action = Action::Resume; // lo que pidió el prompt de -V
+                let (entering, entry) = match info.map(|i| i.stop) {
+                    Some(SyscallStop::Entry { nr, args }) | Some(SyscallStop::Seccomp { nr, args }) => (true, Some((nr, args))),
+                    Some(SyscallStop::Exit { .. }) => (false, None),
+                    None => (st.entering, None),
+                };
+
+                if entering {
+                    // ENTRADA: registra número y muestra args si -v. La ABI sale del campo arch
+                    // de syscall info (detecta `int 0x80` desde código de 64 bits) o, sin él,
+                    // de los registros (segmento CS en x86_64)
+                    st.abi = info.and_then(|i| Abi::from_audit_arch(i.arch)).unwrap_or_else(|| regs.abi());
+                    let (scno, mut args) = entry.unwrap_or_else(|| (regs.syscall_number(st.abi), regs.args(st.abi)));
                     st.last_syscall = scno;
-                    if opts.verbose {
-                        log_sys_enter(pid, scno, &regs);
-                        if opts.very_verbose { wait_keypress(); }
+                    st.args = args;
+                    st.in_syscall = true;
+                    let name = syscall_name(st.abi, scno);
+                    st.traced = opts.qual.trace.matches(st.abi, &name) && opts.qual.paths.matches(pid, st.abi, &name, &args);
+                    // -e inject=: argumentos reescritos y syscall anulada ya en la entrada
+                    st.injected = None;
+                    st.injected_signal.syscall_entry();
+                    if let Some(rule) = injector.select(&opts.qual.inject, st.abi, &name) {
+                        let mut regs = regs;
+                        st.injected = rule.enter(pid, st.abi, &mut regs, &mut args).ok();
+                        st.args = args;
+                        delay = rule.delay_enter;
+                        if let Some(sig) = rule.signal {
+                            let tgid = *st.tgid.get_or_insert_with(|| tgid_of(pid));
+                            if send_signal(tgid, pid, sig).is_ok() {
+                                st.injected_signal.sent(sig);
+                            }
+                        }
+                    }
+                    let now = Instant::now();
+                    if opts.verbose && st.traced {
+                        let ip_sp = opts.instruction_pointer.then(|| {
+                            info.map_or((regs.instruction_pointer(), regs.stack_pointer()), |i| {
+                                (i.instruction_pointer, i.stack_pointer)
+                            })
+                        });
+                        let tgid = *st.tgid.get_or_insert_with(|| tgid_of(pid));
+                        let mut ev = SyscallEvent::enter(pid, tgid, st.abi, scno, args, ip_sp);
+                        ev.delayed = delay.is_some();
+                        if opts.decode_fds {
+                            ev.annotate_fds(&mut fds);
+                        }
+                        if opts.relative {
+                            ev.relative = Some(last_entry.map_or(Duration::ZERO, |t| now - t));
+                        }
+                        log_sys_enter(&mut out, &ev, opts);
+                        st.event = Some(ev);
+                    }
+                    // Los breakpoints de -V detienen también en las syscalls que excluye -e trace=
+                    if opts.very_verbose && debugger.should_pause(&name, st.traced) {
+                        let raw_args = st.event.as_mut().and_then(|ev| ev.raw_args.as_mut());
+                        let nullified = st.injected.as_ref().is_some_and(Injected::nullifies);
+                        action = debugger.prompt(&mut Stop {
+                            pid,
+                            abi: st.abi,
+                            name: &name,
+                            entering: true,
+                            nullified,
+                            args: &mut st.args,
+                            raw_args,
+                        });
+                    }
+                    // La ruta se resuelve en la entrada: después de un close el fd ya no existe.
+                    // Se usan los argumentos finales (tras `set arg` del prompt de -V)
+                    let args = st.args;
+                    if st.traced && opts.histogram == Some(HistogramBy::Path) {
+                        st.fd_path = fd_arg(st.abi, &name).and_then(|i| fd_path(pid, args[i] as i32));
+                    }
+                    if st.traced && opts.io_report {
+                        let tgid = *st.tgid.get_or_insert_with(|| tgid_of(pid));
+                        st.io_targets = io_fds(&name)
+                            .iter()
+                            .filter_map(|&(i, dir)| Some((dir, fds.describe(pid, tgid, args[i] as i32)?)))
+                            .collect();
+                    }
+                    if st.traced {
+                        last_entry = Some(now); // -r mide entre syscalls mostradas
                     }
                     st.entering = false;
                 } else {
-                    // SALIDA: muestra retorno si -v, incrementa conteo
+                    // SALIDA: muestra retorno si -v, incrementa conteo. Si no vimos la entrada
+                    // (p. ej. adjuntamos con -p a mitad de una syscall) el registro del número
+                    // (ORIG_RAX / x8) aún lo conserva, pero los argumentos ya no son fiables.
+                    let seen_entry = st.in_syscall;
+                    if !seen_entry {
+                        st.abi = info.and_then(|i| Abi::from_audit_arch(i.arch)).unwrap_or_else(|| regs.abi());
+                        st.last_syscall = regs.syscall_number(st.abi);
+                        // Sin la entrada no se conocen sus rutas: con -P no se muestra
+                        st.traced = opts.qual.trace.matches(st.abi, &syscall_name(st.abi, st.last_syscall))
+                            && opts.qual.paths.is_empty();
+                    }
                     let scno = st.last_syscall;
-                    let ret = regs.rax as i64; // valor de retorno
-                    if opts.verbose {
-                        log_sys_exit(pid, scno, ret);
-                        if opts.very_verbose { wait_keypress(); }
+                    let mut ret = match info.map(|i| i.stop) {
+                        Some(SyscallStop::Exit { rval }) => rval,
+                        _ => regs.return_value(st.abi), // valor de retorno
+                    };
+                    let inj = st.injected.take().filter(|_| seen_entry);
+                    let injected = inj.as_ref().is_some_and(|i| i.exit(pid, regs, &mut ret).is_ok() && i.alters);
+                    delay = inj.and_then(|i| i.delay_exit);
+                    st.in_syscall = false;
+                    let duration = st.entry_time.filter(|_| seen_entry).map(|t| t.elapsed());
+                    if opts.verbose && st.traced {
+                        let tgid = *st.tgid.get_or_insert_with(|| tgid_of(pid));
+                        let mut ev = match st.event.take() {
+                            Some(ev) if seen_entry => ev,
+                            _ => SyscallEvent::unseen(pid, tgid, st.abi, scno),
+                        };
+                        ev.complete(ret, duration);
+                        ev.injected = injected;
+                        ev.delayed |= delay.is_some();
+                        if opts.decode_fds {
+                            if let Some(raw) = &ev.raw_args {
+                                fds.invalidate(tgid, &ev.name, raw);
+                            }
+                            ev.annotate_ret_fd(&mut fds);
+                        }
+                        log_sys_exit(&mut out, &ev, opts);
+                    }
+                    let name = syscall_name(st.abi, scno);
+                    if opts.very_verbose && debugger.should_pause(&name, st.traced) {
+                        action = debugger.prompt(&mut Stop {
+                            pid,
+                            abi: st.abi,
+                            name: &name,
+                            entering: false,
+                            nullified: false,
+                            args: &mut st.args,
+                            raw_args: None,
+                        });
+                    }
+                    // El modelo de fds ve todas las syscalls, pasen o no el filtro de -e
+                    if opts.fd_report && seen_entry {
+                        let tgid = *st.tgid.get_or_insert_with(|| tgid_of(pid));
+                        fdt.syscall(pid, tgid, st.abi, &syscall_name(st.abi, scno), &st.args, ret);
+                    }
+                    let path = st.fd_path.take().filter(|_| seen_entry);
+                    let io_targets = std::mem::take(&mut st.io_targets);
+                    if st.traced {
+                        stats.record((st.abi, scno), duration, ret, path, injected);
+                        if seen_entry && ret >= 0 {
+                            stats.record_io(&io_targets, duration, ret as u64);
+                        }
                     }
-                    *counts.entry(scno).or_insert(0) += 1;
-                    total_calls += 1;
                     st.entering = true;
                 }
 
-                // Continuar
-                ptrace::syscall(pid, None).unwrap();
+                // `d` en el prompt de -V: este tracee está en un stop, se suelta directamente
+                if action == Action::Detach {
+                    let own = usize::from(ptrace::detach(pid, None).is_ok());
+                    per_tid.remove(&pid);
+                    for pid in sched.release_all() {
+                        resume(pid, None);
+                    }
+                    let detached = own + detach_all(&per_tid);
+                    eprintln!("[rastreador] Desadjuntado de {} tracee(s)", detached);
+                    finish(&mut out, &stats, &fdt, 0, opts);
+                }
+
+                // Continuar (o retener hasta el plazo de delay_enter/delay_exit)
+                match delay {
+                    Some(delay) => sched.hold(pid, delay),
+                    None => resume_syscall(&mut per_tid, pid),
+                }
             }
             Ok(WaitStatus::Stopped(pid, sig)) => {
+                // SIGSTOP inicial de un tracee recién creado: se consume, no se reinyecta.
+                // Puede llegar antes que el PtraceEvent del padre (pid aún desconocido).
+                if opts.follow_forks && sig == Signal::SIGSTOP {
+                    match per_tid.get_mut(&pid) {
+                        None => {
+                            per_tid.insert(pid, ThreadState { tgid: Some(tgid_of(pid)), ..Default::default() });
+                            resume(pid, None);
+                            continue;
+                        }
+                        Some(st) if st.pending_sigstop => {
+                            st.pending_sigstop = false;
+                            resume(pid, None);
+                            continue;
+                        }
+                        Some(_) => {}
+                    }
+                }
+                // La señal de -e inject=signal se reinyecta como cualquier otra, pero se informa;
+                // strace informa además toda entrega de señal con su siginfo
+                if per_tid.get_mut(&pid).is_some_and(|st| st.injected_signal.delivered(sig)) {
+                    if opts.verbose {
+                        log_process(&mut out, pid, opts, ProcEvent::InjectedSignal(sig));
+                    }
+                } else if opts.verbose && opts.format == Format::Strace {
+                    log_process(&mut out, pid, opts, ProcEvent::Signal(sig));
+                }
                 // Stop por señal distinta; reinyecta señal al hijo
-                let signo = Some(sig);
-                ptrace::syscall(pid, signo).unwrap();
+                resume(pid, Some(sig));
             }
-            Ok(WaitStatus::PtraceEvent(pid, sig, _code)) => {
-                // No seguimos forks/hilos; solo continuar
-                let signo = Some(sig);
-                ptrace::syscall(pid, signo).unwrap();
+            Ok(WaitStatus::PtraceEvent(pid, sig, libc::PTRACE_EVENT_STOP)) => {
+                // Stop de un tracee adjunto con SEIZE: por PTRACE_INTERRUPT (SIGTRAP), el
+                // arranque de un hijo nuevo, o un group-stop real (SIGSTOP/SIGTSTP/...)
+                let st = per_tid.entry(pid).or_default();
+                st.pending_sigstop = false;
+                if sig == Signal::SIGTRAP {
+                    resume(pid, None);
+                } else {
+                    // Group-stop: LISTEN lo deja detenido sin perder los eventos de ptrace
+                    listen(pid);
+                }
+            }
+            Ok(WaitStatus::PtraceEvent(pid, _sig, event)) => {
+                // fork/vfork/clone: registra el nuevo TID (ya quedó trazado por el kernel)
+                if event == libc::PTRACE_EVENT_FORK
+                    || event == libc::PTRACE_EVENT_VFORK
+                    || event == libc::PTRACE_EVENT_CLONE
+                {
+                    if let Ok(new_tid) = ptrace::getevent(pid) {
+                        let new_tid = Pid::from_raw(new_tid as i32);
+                        // Un proceso nuevo (no un hilo) arranca con una copia de la tabla de fds
+                        let (parent_tgid, child_tgid) = (tgid_of(pid), tgid_of(new_tid));
+                        if opts.fd_report && parent_tgid != child_tgid {
+                            fdt.fork(pid, parent_tgid, child_tgid);
+                        }
+                        per_tid
+                            .entry(new_tid)
+                            .or_insert_with(|| ThreadState { pending_sigstop: true, ..Default::default() })
+                            .tgid = Some(child_tgid);
+                        if opts.verbose {
+                            log_process(&mut out, pid, opts, ProcEvent::New(new_tid));
+                        }
+                    }
+                }
+                // El SIGTRAP del evento (fork/clone/exec) no pertenece al tracee: continuar sin señal
+                resume(pid, None);
             }
             Ok(other) => {
-                // Otros estados (StillAlive, etc.)
+                // Otros estados
                 eprintln!("[rastreador] Estado: {other:?}");
             }
             Err(e) => match e {
-                nix::Error::ECHILD => {
+                Errno::ECHILD => {
                     // Sin hijos: terminó
-                    print_summary(&counts, total_calls);
-                    std::process::exit(0);
+                    finish(&mut out, &stats, &fdt, root_status, opts);
                 }
+                Errno::EINTR => continue,
                 _ => panic!("waitpid falló: {e}"),
             },
         }
     }
 }
 
+/// Cierre común del rastreo: restaura la terminal (por si -V la dejó en modo raw),
+/// imprime el resumen y termina con el código indicado.
+fn finish(out: &mut Output, stats: &Stats, fdt: &FdTables, code: i32, opts: &Opts) -> ! {
+    if is_raw_mode_enabled().unwrap_or(false) {
+        let _ = disable_raw_mode();
+    }
+    let mut w = out.summary();
+    let res = match opts.format {
+        Format::Text | Format::Strace => print_summary(&mut w, stats, opts.summary_sort_by)
+            .and_then(|_| if opts.io_report { print_io_report(&mut w, stats) } else { Ok(()) })
+            .and_then(|_| if opts.fd_report { print_fd_report(&mut w, fdt) } else { Ok(()) }),
+        Format::Jsonl => print_summary_json(&mut w, stats, opts.summary_sort_by)
+            .and_then(|_| if opts.io_report { print_io_report_json(&mut w, stats) } else { Ok(()) })
+            .and_then(|_| if opts.fd_report { print_fd_report_json(&mut w, fdt) } else { Ok(()) }),
+    };
+    drop(w);
+    if let Err(e) = res {
+        eprintln!("[rastreador] Error escribiendo el resumen: {e}");
+    }
+    out.flush();
+    std::process::exit(code);
+}
+
+/// Un hilo terminó: saca su estado y, si era el último de su proceso, da por cerrada la
+/// tabla de fds del proceso (-y, --fd-report). El líder puede salir antes que el resto
+/// (pthread_exit), así que no basta con que termine el TID igual al TGID.
+fn thread_exit(
+    per_tid: &mut HashMap<Pid, ThreadState>,
+    fds: &mut FdCache,
+    fdt: &mut FdTables,
+    pid: Pid,
+) -> Option<ThreadState> {
+    let st = per_tid.remove(&pid);
+    let tgid = st.as_ref().and_then(|st| st.tgid).unwrap_or(pid.as_raw());
+    if !per_tid.values().any(|other| other.tgid == Some(tgid)) {
+        fds.forget_process(tgid);
+        fdt.process_exit(tgid);
+    }
+    st
+}
+
+/// Desadjunta todos los tracees y devuelve cuántos se soltaron. PTRACE_DETACH exige
+/// que el tracee esté en un stop: los adjuntados con SEIZE se detienen con
+/// PTRACE_INTERRUPT; los de TRACEME (Prog y sus hijos con -f) no lo admiten (EIO) y se
+/// detienen con un SIGSTOP propio, que se consume al desadjuntar.
+fn detach_all(per_tid: &HashMap<Pid, ThreadState>) -> usize {
+    let mut detached = 0;
+    for (&tid, st) in per_tid {
+        let sigstop = match ptrace::interrupt(tid) {
+            Ok(()) => false,
+            Err(Errno::EIO) => {
+                let tgid = st.tgid.unwrap_or_else(|| tgid_of(tid));
+                if send_signal(tgid, tid, Signal::SIGSTOP).is_err() {
+                    continue; // ya no existe
+                }
+                true
+            }
+            Err(_) => continue, // ya no existe
+        };
+        if stop_for_detach(tid, sigstop) {
+            detached += 1;
+        }
+    }
+    detached
+}
+
+/// Espera el stop de `tid` pedido por detach_all y lo desadjunta; false si el tracee
+/// terminó antes. Con `sigstop` solo vale el stop de entrega de ese SIGSTOP (si se
+/// soltara en otro stop quedaría pendiente y detendría al proceso): los stops previos
+/// se dejan seguir, y las señales reales se entregan por el camino.
+fn stop_for_detach(tid: Pid, sigstop: bool) -> bool {
+    loop {
+        match waitpid(tid, Some(WaitPidFlag::__WALL)) {
+            Ok(WaitStatus::Stopped(_, Signal::SIGSTOP)) if sigstop => return ptrace::detach(tid, None).is_ok(),
+            Ok(WaitStatus::Stopped(_, sig)) if sigstop => {
+                let _ = ptrace::cont(tid, sig);
+            }
+            Ok(WaitStatus::PtraceSyscall(_)) | Ok(WaitStatus::PtraceEvent(..)) if sigstop => {
+                let _ = ptrace::cont(tid, None);
+            }
+            // Señal real pendiente: se entrega al desadjuntar para no perderla
+            Ok(WaitStatus::Stopped(_, sig)) if sig != Signal::SIGSTOP && sig != Signal::SIGTRAP => {
+                return ptrace::detach(tid, Some(sig)).is_ok();
+            }
+            Ok(WaitStatus::Stopped(..)) | Ok(WaitStatus::PtraceSyscall(_)) | Ok(WaitStatus::PtraceEvent(..)) => {
+                return ptrace::detach(tid, None).is_ok();
+            }
+            Ok(WaitStatus::Exited(..)) | Ok(WaitStatus::Signaled(..)) | Err(Errno::ECHILD) => return false,
+            Err(Errno::EINTR) | Ok(_) => continue,
+            Err(_) => return false,
+        }
+    }
+}
+
+/// Reanuda un tracee hasta el próximo stop de syscall. Ignora ESRCH: con varios
+/// hilos, un tracee puede morir (exit_group, SIGKILL) entre el stop y la reanudación.
+fn resume(pid: Pid, sig: Option<Signal>) {
+    match ptrace::syscall(pid, sig) {
+        Ok(()) | Err(Errno::ESRCH) => {}
+        Err(e) => panic!("ptrace(SYSCALL) falló para {pid}: {e}"),
+    }
+}
+
+/// Deja a un tracee en su group-stop con PTRACE_LISTEN (sigue detenido, pero reporta
+/// el SIGCONT y los eventos de ptrace). Si falla por algo distinto de ESRCH se reanuda
+/// para no dejarlo en un ptrace-stop del que nada lo sacaría.
+fn listen(pid: Pid) {
+    match Errno::result(unsafe { libc::ptrace(libc::PTRACE_LISTEN, pid.as_raw(), 0, 0) }) {
+        Ok(_) | Err(Errno::ESRCH) => {}
+        Err(e) => {
+            eprintln!("[rastreador] ptrace(LISTEN) falló para {pid}: {e}; se reanuda");
+            resume(pid, None);
+        }
+    }
+}
+
+/// Reanuda un tracee detenido en un stop de syscall. Tras la entrada, el tiempo de la
+/// syscall empieza a correr aquí: no incluye la retención de delay_enter ni la pausa
+/// del prompt de -V.
+fn resume_syscall(per_tid: &mut HashMap<Pid, ThreadState>, pid: Pid) {
+    if let Some(st) = per_tid.get_mut(&pid).filter(|st| st.in_syscall) {
+        st.entry_time = Some(Instant::now());
+    }
+    resume(pid, None);
+}
+
+/// Prefijo de cada línea de log: con -f o -p se indica el pid como hace strace (salvo
+/// con --output-separately, donde cada proceso ya tiene su archivo).
+fn pid_prefix(pid: Pid, opts: &Opts) -> String {
+    if opts.output_separately || (!opts.follow_forks && opts.pids.is_empty()) {
+        String::new()
+    } else if opts.format == Format::Strace {
+        format!("[pid {:>5}] ", pid)
+    } else {
+        format!("[pid {:>6}] ", pid)
+    }
+}
+
+/// Prefijo de las líneas de syscall: pid y, con -i, IP y SP (strace muestra solo la IP).
+fn syscall_prefix(ev: &SyscallEvent, opts: &Opts) -> String {
+    let mut prefix = pid_prefix(ev.tid, opts);
+    if opts.timestamps > 0 {
+        prefix.push_str(&format!("{} ", fmt_timestamp(ev.timestamp, opts.timestamps)));
+    }
+    if let Some(rel) = ev.relative {
+        prefix.push_str(&format!("{:>13} ", fmt_duration(rel)));
+    }
+    match ev.ip_sp {
+        Some((ip, _)) if opts.format == Format::Strace => prefix.push_str(&format!("[{:016x}] ", ip)),
+        Some((ip, sp)) => prefix.push_str(&format!("[ip=0x{:x} sp=0x{:x}] ", ip, sp)),
+        None => {}
+    }
+    prefix
+}
+
+/// Prefijo de las líneas que completan una syscall en otra línea (`←`, `<... resumed>`):
+/// pid y la marca de tiempo de la salida.
+fn exit_prefix(ev: &SyscallEvent, opts: &Opts) -> String {
+    let mut prefix = pid_prefix(ev.tid, opts);
+    if opts.timestamps > 0 {
+        prefix.push_str(&format!("{} ", fmt_timestamp(ev.exit_time(), opts.timestamps)));
+    }
+    prefix
+}
+
+/// Sufijo de -T: ` <0.000012>` con el tiempo pasado en la syscall.
+fn duration_suffix(ev: &SyscallEvent, opts: &Opts) -> String {
+    match ev.duration {
+        Some(d) if opts.syscall_times => format!(" <{}>", fmt_duration(d)),
+        _ => String::new(),
+    }
+}
+
 fn wait_for_any_stop(child: Pid) {
     loop {
         match waitpid(Some(child), None) {
@@ -190,103 +906,126 @@ fn wait_for_any_stop(child: Pid) {
     }
 }
 
-fn ptrace_getregs(pid: Pid) -> nix::Result<libc::user_regs_struct> {
-    ptrace::getregs(pid)
+/// Con -e status= (o -z/-Z) la línea de entrada se retiene hasta la salida, que decide
+/// según el retorno si la syscall se muestra.
+fn deferred(opts: &Opts) -> bool {
+    !opts.qual.status.is_all()
 }
 
-fn log_sys_enter(pid: Pid, scno: u64, regs: &libc::user_regs_struct) {
-    let name = syscall_name(scno);
-    // Registros de argumentos en x86_64 Linux
-    let (a0, a1, a2, a3, a4, a5) = (regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9);
+fn log_sys_enter(out: &mut Output, ev: &SyscallEvent, opts: &Opts) {
+    if deferred(opts) {
+        return;
+    }
+    match opts.format {
+        Format::Text => out.line(ev.tid, &format!("{}→ {}", syscall_prefix(ev, opts), ev.call_text())),
+        // strace: la línea queda abierta hasta la salida (o se corta si otro tid escribe antes)
+        Format::Strace => out.begin(ev.tid, format!("{}{}({}", syscall_prefix(ev, opts), ev.name, ev.strace_split().0)),
+        // En jsonl solo se emite el evento completo, en la salida
+        Format::Jsonl => {}
+    }
+}
 
-    // Decodificación especial para algunas syscalls comunes
-    if scno == libc::SYS_execve as u64 {
-        let path = read_c_string(pid, a0).unwrap_or_else(|_| format!("<ptr 0x{a0:x}>"));
-        // argv: **char -> vector de CStrings (limitado)
-        let argv_preview = read_argv_preview(pid, a1, 6);
-        eprintln!("→ {}(pathname=\\{:?}\\, argv={:?}, envp=0x{:x})", name, path, argv_preview, a2);
+fn log_sys_exit(out: &mut Output, ev: &SyscallEvent, opts: &Opts) {
+    if !opts.qual.status.matches(ev.status()) {
         return;
     }
-    if scno == libc::SYS_openat as u64 {
-        let dirfd = a0 as i64;
-        let path = read_c_string(pid, a1).unwrap_or_else(|_| format!("<ptr 0x{a1:x}>"));
-        let flags = a2 as i32;
-        let mode = a3 as u32;
-        eprintln!(
-            "→ {}(dirfd={}, pathname=\\{:?}\\, flags={}, mode=0{:o})",
-            name,
-            dirfd,
-            path,
-            fmt_flags_open(flags),
-            mode
-        );
+    match opts.format {
+        Format::Text => {
+            if deferred(opts) && ev.raw_args.is_some() {
+                out.line(ev.tid, &format!("{}→ {}", syscall_prefix(ev, opts), ev.call_text()));
+            }
+            let line = format!("{}← {}{}", exit_prefix(ev, opts), ev.return_text(), duration_suffix(ev, opts));
+            out.line(ev.tid, &line)
+        }
+        Format::Strace => {
+            // Se reimprimen los argumentos: los de salida ya están decodificados
+            let (ret, dur) = (ev.strace_ret(), duration_suffix(ev, opts));
+            let whole = out.take_pending(ev.tid).is_some() || (deferred(opts) && ev.raw_args.is_some());
+            let line = if whole {
+                format!("{}{}({}) {}{}", syscall_prefix(ev, opts), ev.name, ev.strace_args(), ret, dur)
+            } else {
+                format!("{}<... {} resumed>{}) {}{}", exit_prefix(ev, opts), ev.name, ev.strace_split().1, ret, dur)
+            };
+            out.line(ev.tid, &line);
+        }
+        Format::Jsonl => out.line(ev.tid, &ev.to_json().to_string()),
+    }
+}
+
+/// El tracee terminó dentro de una syscall que no retorna (exit_group, o un execve de
+/// otro hilo). En strace su línea se cierra con `= ?`; si la entrada se retuvo por
+/// -e status=, se imprime ahora.
+fn log_vanished(out: &mut Output, pid: Pid, st: Option<ThreadState>, opts: &Opts) {
+    let pending = out.take_pending(pid);
+    let Some(ev) = st.filter(|st| st.in_syscall).and_then(|st| st.event) else {
+        return;
+    };
+    if !opts.qual.status.matches(SyscallStatus::Unfinished) {
         return;
     }
+    match opts.format {
+        Format::Strace if pending.is_some() || deferred(opts) => {
+            out.line(pid, &format!("{}{}({}) = ?", syscall_prefix(&ev, opts), ev.name, ev.strace_args()))
+        }
+        Format::Strace => out.line(pid, &format!("{}<... {} resumed>) = ?", exit_prefix(&ev, opts), ev.name)),
+        Format::Text if deferred(opts) => out.line(pid, &format!("{}→ {}", syscall_prefix(&ev, opts), ev.call_text())),
+        Format::Text | Format::Jsonl => {}
+    }
+}
 
-    // Genérico (muestra hex y decimal)
-    eprintln!(
-        "→ {}(0x{:x}, 0x{:x}, 0x{:x}, 0x{:x}, 0x{:x}, 0x{:x})",
-        name, a0, a1, a2, a3, a4, a5
-    );
+/// Eventos de proceso que se registran en la traza.
+enum ProcEvent {
+    New(Pid),
+    Exited(i32),
+    Killed(Signal),
+    Signal(Signal),         // entrega de una señal (solo con --strace-compat)
+    InjectedSignal(Signal), // entrega de una señal de -e inject=signal
 }
 
-fn log_sys_exit(_pid: Pid, scno: u64, ret: i64) {
-    let name = syscall_name(scno);
-    if ret < 0 && ret >= -4095 {
-        let errno = -ret as i32;
-        eprintln!("← {} = -1 {}", name, decode_errno(errno));
-    } else {
-        eprintln!("← {} = {}", name, ret);
-    }
-}
-
-fn wait_keypress() {
-    eprint!("(V) Presiona cualquier tecla para continuar… ");
-    let _ = io::stdout().flush();
-    // Habilita modo raw para no requerir Enter
-    let _ = enable_raw_mode();
-    let _ = read(); // bloquea hasta cualquier evento de teclado
-    let _ = disable_raw_mode();
-    eprintln!("");
-}
-
-fn read_argv_preview(pid: Pid, argv_ptr: u64, max_items: usize) -> Vec<String> {
-    let mut out = Vec::new();
-    let mut i = 0usize;
-    let word_size = size_of::<usize>() as u64;
-    let mut p = argv_ptr;
-    while i < max_items {
-        match read_ptr(pid, p) {
-            Ok(0) => break, // NULL termina
-            Ok(s_ptr) => {
-                let s = read_c_string(pid, s_ptr as u64)
-                    .unwrap_or_else(|_| format!("<ptr 0x{:x}>", s_ptr));
-                out.push(s);
-                p += word_size;
-                i += 1;
+/// Eventos de proceso (`+++ ... +++`); en jsonl, un objeto `{"type":"process",...}`.
+fn log_process(out: &mut Output, pid: Pid, opts: &Opts, event: ProcEvent) {
+    let prefix = pid_prefix(pid, opts);
+    match (opts.format, event) {
+        (Format::Text, ProcEvent::New(tid)) => out.line(pid, &format!("{}+++ nuevo tracee {} +++", prefix, tid)),
+        (Format::Text, ProcEvent::Exited(status)) => {
+            out.line(pid, &format!("{}+++ terminó con estado {} +++", prefix, status))
+        }
+        (Format::Text, ProcEvent::Killed(sig)) => out.line(pid, &format!("{}+++ terminó por señal {sig:?} +++", prefix)),
+        (Format::Text, ProcEvent::Signal(_)) => {}
+        (Format::Text, ProcEvent::InjectedSignal(sig)) => {
+            out.line(pid, &format!("{}--- {} (inyectada) ---", prefix, sig.as_str()))
+        }
+        // strace no informa los tracees nuevos (salvo por el retorno de clone/fork)
+        (Format::Strace, ProcEvent::New(_)) => {}
+        (Format::Strace, ProcEvent::Exited(status)) => out.line(pid, &format!("{}+++ exited with {} +++", prefix, status)),
+        (Format::Strace, ProcEvent::Killed(sig)) => out.line(pid, &format!("{}+++ killed by {} +++", prefix, sig.as_str())),
+        (Format::Strace, ProcEvent::Signal(sig)) => {
+            out.line(pid, &format!("{}--- {} {} ---", prefix, sig.as_str(), strace_siginfo(pid)))
+        }
+        (Format::Strace, ProcEvent::InjectedSignal(sig)) => {
+            out.line(pid, &format!("{}--- {} {} (INJECTED) ---", prefix, sig.as_str(), strace_siginfo(pid)))
+        }
+        (Format::Jsonl, event) => {
+            let fields = match event {
+                ProcEvent::New(tid) => json!({"event": "new", "new_tid": tid.as_raw()}),
+                ProcEvent::Exited(status) => json!({"event": "exited", "status": status}),
+                ProcEvent::Killed(sig) => json!({"event": "killed", "signal": sig.as_str()}),
+                ProcEvent::Signal(sig) => json!({"event": "signal", "signal": sig.as_str()}),
+                ProcEvent::InjectedSignal(sig) => json!({"event": "signal", "signal": sig.as_str(), "injected": true}),
+            };
+            let mut obj = json!({"type": "process", "tid": pid.as_raw()});
+            if let (Some(obj), Some(fields)) = (obj.as_object_mut(), fields.as_object()) {
+                obj.extend(fields.clone());
             }
-            Err(_) => break,
+            out.line(pid, &obj.to_string());
         }
     }
-    if i == max_items {
-        out.push("…".to_string());
-    }
-    out
 }
 
-fn print_summary(counts: &HashMap<u64, u64>, total: u64) {
-    use std::cmp::Reverse;
-    let mut v: Vec<(u64, u64)> = counts.iter().map(|(k, c)| (*k, *c)).collect();
-    v.sort_by_key(|&(_k, c)| Reverse(c));
-
-    println!("\\n===== RESUMEN DE SYSCALLS =====");
-    println!("{:24}  {:>10}  {:>8}", "Syscall", "Conteo", "%");
-    println!("{:-<24}  {:-<10}  {:-<8}", "", "", "");
-    let total_f = total as f64;
-    for (scno, c) in v {
-        let name = syscall_name(scno);
-        let pct = if total > 0 { (c as f64) * 100.0 / total_f } else { 0.0 };
-        println!("{:24}  {:>10}  {:>7.2}", name, c, pct);
+/// siginfo de la señal que el tracee tiene pendiente en su stop de entrega (`{si_signo=...}`).
+fn strace_siginfo(pid: Pid) -> String {
+    match ptrace::getsiginfo(pid) {
+        Ok(info) => sysdecode::fmt_siginfo(&info),
+        Err(_) => "{}".to_string(),
     }
-    println!("Total syscalls observadas: {}", total);
 }
@@ -0,0 +1,220 @@
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// === Destino de la traza ===
+// Por defecto las líneas de eventos van a stderr y el resumen a stdout. Con -o FILE
+// ambos van a un archivo con buffer; con --output-separately cada proceso trazado
+// escribe sus eventos en FILE.<pid> y el resumen queda en FILE.
+
+// Con --strace-compat la entrada de una syscall queda pendiente (`begin`) hasta su
+// salida; si antes se escribe una línea de otro tracee, la pendiente se corta con
+// ` <unfinished ...>` como hace strace.
+
+pub struct Output {
+    sink: Sink,
+    pending: Option<(Pid, String)>, // entrada sin terminar: tid y texto de la línea
+}
+
+enum Sink {
+    Stderr,
+    File(BufWriter<File>),
+    PerProcess {
+        base: String,
+        summary: BufWriter<File>,
+        files: HashMap<i32, BufWriter<File>>, // por TGID
+        tgids: HashMap<Pid, i32>,             // TID → TGID (cacheado: /proc desaparece al salir)
+    },
+}
+
+impl Output {
+    pub fn open(path: Option<&str>, separately: bool) -> io::Result<Output> {
+        let sink = match path {
+            None => Sink::Stderr,
+            Some(p) if separately => Sink::PerProcess {
+                base: p.to_string(),
+                summary: BufWriter::new(File::create(p)?),
+                files: HashMap::new(),
+                tgids: HashMap::new(),
+            },
+            Some(p) => Sink::File(BufWriter::new(File::create(p)?)),
+        };
+        Ok(Output { sink, pending: None })
+    }
+
+    /// Escribe una línea de evento del tracee `pid` (cortando antes la línea pendiente).
+    pub fn line(&mut self, pid: Pid, text: &str) {
+        if let Some((tid, pending)) = self.pending.take() {
+            self.sink.write_line(tid, &format!("{} <unfinished ...>", pending));
+        }
+        self.sink.write_line(pid, text);
+    }
+
+    /// Deja pendiente el comienzo de una línea de `pid` hasta que se complete.
+    pub fn begin(&mut self, pid: Pid, text: String) {
+        if let Some((tid, pending)) = self.pending.replace((pid, text)) {
+            self.sink.write_line(tid, &format!("{} <unfinished ...>", pending));
+        }
+    }
+
+    /// Retira la línea pendiente si es de `pid` (para completarla); None si no la hay o
+    /// ya se cortó como `<unfinished ...>`.
+    pub fn take_pending(&mut self, pid: Pid) -> Option<String> {
+        match self.pending.take() {
+            Some((tid, text)) if tid == pid => Some(text),
+            other => {
+                self.pending = other;
+                None
+            }
+        }
+    }
+
+    /// Olvida el TGID cacheado de `pid` tras su última línea: el kernel reutiliza los
+    /// TIDs y un proceso posterior con el mismo no debe escribir en el archivo del muerto.
+    pub fn forget(&mut self, pid: Pid) {
+        if let Sink::PerProcess { tgids, .. } = &mut self.sink {
+            tgids.remove(&pid);
+        }
+    }
+
+    /// Destino del resumen final (stdout si no hay -o).
+    pub fn summary(&mut self) -> Box<dyn Write + '_> {
+        if let Some((tid, pending)) = self.pending.take() {
+            self.sink.write_line(tid, &format!("{} <unfinished ...>", pending));
+        }
+        match &mut self.sink {
+            Sink::Stderr => Box::new(io::stdout()),
+            Sink::File(w) => Box::new(w),
+            Sink::PerProcess { summary, .. } => Box::new(summary),
+        }
+    }
+
+    pub fn flush(&mut self) {
+        let _ = match &mut self.sink {
+            Sink::Stderr => io::stdout().flush(),
+            Sink::File(w) => w.flush(),
+            Sink::PerProcess { summary, files, .. } => {
+                for w in files.values_mut() {
+                    let _ = w.flush();
+                }
+                summary.flush()
+            }
+        };
+    }
+}
+
+impl Sink {
+    fn write_line(&mut self, pid: Pid, text: &str) {
+        let res = match self {
+            Sink::Stderr => writeln!(io::stderr(), "{}", text),
+            Sink::File(w) => writeln!(w, "{}", text),
+            Sink::PerProcess { base, files, tgids, .. } => {
+                let tgid = *tgids.entry(pid).or_insert_with(|| tgid_of(pid));
+                let w = match files.entry(tgid) {
+                    std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+                    std::collections::hash_map::Entry::Vacant(e) => {
+                        match File::create(format!("{}.{}", base, tgid)) {
+                            Ok(f) => e.insert(BufWriter::new(f)),
+                            Err(err) => {
+                                eprintln!("[rastreador] No se pudo crear {}.{}: {}", base, tgid, err);
+                                return;
+                            }
+                        }
+                    }
+                };
+                writeln!(w, "{}", text)
+            }
+        };
+        if let Err(e) = res {
+            eprintln!("[rastreador] Error escribiendo la traza: {}", e);
+        }
+    }
+}
+
+/// TGID (PID del proceso) de un TID según /proc; si no se puede leer, el propio TID.
+pub fn tgid_of(tid: Pid) -> i32 {
+    std::fs::read_to_string(format!("/proc/{}/status", tid))
+        .ok()
+        .and_then(|s| {
+            s.lines()
+                .find_map(|l| l.strip_prefix("Tgid:"))
+                .and_then(|v| v.trim().parse().ok())
+        })
+        .unwrap_or(tid.as_raw())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let dir = std::env::temp_dir();
+        format!("{}/rastreador-{}-{}", dir.display(), std::process::id(), name)
+    }
+
+    #[test]
+    fn lineas_pendientes() {
+        let path = temp_path("pendientes");
+        let mut out = Output::open(Some(&path), false).unwrap();
+        let (a, b) = (Pid::from_raw(100), Pid::from_raw(101));
+
+        // Se completa sin interrupción: la pendiente se retira entera
+        out.begin(a, "read(3, ".to_string());
+        assert_eq!(out.take_pending(b), None);
+        assert_eq!(out.take_pending(a).as_deref(), Some("read(3, "));
+        out.line(a, "read(3, \"x\", 1) = 1");
+
+        // Otro tracee escribe antes: la pendiente se corta y ya no se puede retirar
+        out.begin(a, "wait4(-1, ".to_string());
+        out.line(b, "getpid() = 101");
+        assert_eq!(out.take_pending(a), None);
+        out.line(a, "<... wait4 resumed>) = 101");
+
+        // Una nueva entrada corta la anterior; el resumen corta la última
+        out.begin(a, "pause(".to_string());
+        out.begin(b, "pause(".to_string());
+        let _ = out.summary();
+        out.flush();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            text,
+            "read(3, \"x\", 1) = 1\n\
+             wait4(-1,  <unfinished ...>\n\
+             getpid() = 101\n\
+             <... wait4 resumed>) = 101\n\
+             pause( <unfinished ...>\n\
+             pause( <unfinished ...>\n"
+        );
+    }
+
+    #[test]
+    fn un_archivo_por_proceso() {
+        let base = temp_path("separado");
+        let mut out = Output::open(Some(&base), true).unwrap();
+        // TIDs sin entrada en /proc: su TGID es el propio TID salvo que esté cacheado
+        let (leader, thread) = (Pid::from_raw(0x7fff_fff0), Pid::from_raw(0x7fff_fff1));
+        if let Sink::PerProcess { tgids, .. } = &mut out.sink {
+            tgids.insert(thread, leader.as_raw());
+        }
+        out.line(leader, "leader");
+        out.line(thread, "hilo");
+        // Tras olvidarlo, un TID reutilizado ya no escribe en el archivo del proceso muerto
+        out.forget(thread);
+        out.line(thread, "reutilizado");
+        writeln!(out.summary(), "resumen").unwrap();
+        out.flush();
+
+        let read = |suffix: String| {
+            let path = format!("{}{}", base, suffix);
+            let text = std::fs::read_to_string(&path).unwrap();
+            let _ = std::fs::remove_file(&path);
+            text
+        };
+        assert_eq!(read(format!(".{}", leader)), "leader\nhilo\n");
+        assert_eq!(read(format!(".{}", thread)), "reutilizado\n");
+        assert_eq!(read(String::new()), "resumen\n");
+    }
+}
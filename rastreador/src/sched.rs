@@ -0,0 +1,132 @@
+use nix::poll::{ppoll, PollFd, PollFlags};
+use nix::sys::signal::{SigSet, Signal};
+use nix::sys::signalfd::{SfdFlags, SignalFd};
+use nix::sys::time::TimeSpec;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+// === Planificador de reanudaciones diferidas (-e inject=...:delay_enter/delay_exit) ===
+// Un tracee demorado no se reanuda al terminar de procesar su stop: queda retenido hasta
+// su plazo. Mientras haya alguno retenido, parent_trace espera con Scheduler::waitpid,
+// que vuelve con el próximo cambio de estado de un tracee o al vencer el primer plazo.
+// Para esperar ambas cosas a la vez SIGCHLD se bloquea y se lee de un signalfd: tras
+// vaciarlo, waitpid con WNOHANG recoge lo ya ocurrido y ppoll duerme hasta el plazo o
+// hasta el siguiente SIGCHLD, así no se pierde ninguno que llegue entre ambos pasos.
+
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    held: BinaryHeap<Reverse<(Instant, Pid)>>, // (plazo, tid), el más próximo arriba
+    sigchld: Option<SignalFd>,                 // se crea en la primera espera con plazo
+}
+
+impl Scheduler {
+    /// Retiene al tracee `pid` (detenido en un stop) durante `delay`.
+    pub fn hold(&mut self, pid: Pid, delay: Duration) {
+        self.held.push(Reverse((Instant::now() + delay, pid)));
+    }
+
+    /// Tracees cuyo plazo ya venció (quedan liberados: hay que reanudarlos).
+    pub fn due(&mut self) -> Vec<Pid> {
+        let now = Instant::now();
+        let mut due = Vec::new();
+        while let Some(&Reverse((deadline, pid))) = self.held.peek() {
+            if deadline > now {
+                break;
+            }
+            self.held.pop();
+            due.push(pid);
+        }
+        due
+    }
+
+    /// Cuánto falta para el próximo plazo, o None si no hay nadie retenido.
+    fn timeout(&self) -> Option<Duration> {
+        let Reverse((deadline, _)) = self.held.peek()?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// waitpid(__WALL) que con tracees retenidos vuelve además con StillAlive al vencer
+    /// el primer plazo. Una señal atrapada (SIGINT, SIGTERM) la corta con EINTR.
+    pub fn waitpid(&mut self) -> nix::Result<WaitStatus> {
+        let Some(timeout) = self.timeout() else {
+            return waitpid(None, Some(WaitPidFlag::__WALL));
+        };
+        let sigchld = match &mut self.sigchld {
+            Some(fd) => fd,
+            none => none.insert(sigchld_fd()?),
+        };
+        while sigchld.read_signal()?.is_some() {}
+        match waitpid(None, Some(WaitPidFlag::__WALL | WaitPidFlag::WNOHANG))? {
+            WaitStatus::StillAlive => {}
+            status => return Ok(status),
+        }
+        let mut fds = [PollFd::new(&*sigchld, PollFlags::POLLIN)];
+        ppoll(&mut fds, Some(TimeSpec::from_duration(timeout)), None)?;
+        Ok(WaitStatus::StillAlive)
+    }
+
+    /// Libera a todos los retenidos (al desadjuntar o terminar el rastreo).
+    pub fn release_all(&mut self) -> Vec<Pid> {
+        self.held.drain().map(|Reverse((_, pid))| pid).collect()
+    }
+}
+
+/// signalfd no bloqueante para SIGCHLD, que queda bloqueada para no perderla.
+fn sigchld_fd() -> nix::Result<SignalFd> {
+    let mut mask = SigSet::empty();
+    mask.add(Signal::SIGCHLD);
+    mask.thread_block()?;
+    SignalFd::with_flags(&mask, SfdFlags::SFD_NONBLOCK | SfdFlags::SFD_CLOEXEC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held_at(s: &mut Scheduler, deadline: Instant, tid: i32) {
+        s.held.push(Reverse((deadline, Pid::from_raw(tid))));
+    }
+
+    #[test]
+    fn sin_retenidos() {
+        let mut s = Scheduler::default();
+        assert_eq!(s.timeout(), None);
+        assert!(s.due().is_empty());
+        assert!(s.release_all().is_empty());
+    }
+
+    #[test]
+    fn plazos_en_orden() {
+        let now = Instant::now();
+        let mut s = Scheduler::default();
+        held_at(&mut s, now - Duration::from_millis(2), 10);
+        held_at(&mut s, now + Duration::from_secs(3600), 11);
+        held_at(&mut s, now - Duration::from_millis(5), 12);
+        s.hold(Pid::from_raw(13), Duration::from_secs(60));
+
+        // Los vencidos salen del más antiguo al más reciente; el resto sigue retenido
+        assert_eq!(s.due(), [Pid::from_raw(12), Pid::from_raw(10)]);
+        assert!(s.due().is_empty());
+        let timeout = s.timeout().unwrap();
+        assert!(timeout > Duration::from_secs(59) && timeout <= Duration::from_secs(60));
+
+        let mut rest = s.release_all();
+        rest.sort();
+        assert_eq!(rest, [Pid::from_raw(11), Pid::from_raw(13)]);
+        assert_eq!(s.timeout(), None);
+    }
+
+    #[test]
+    fn mismo_plazo() {
+        let deadline = Instant::now() - Duration::from_millis(1);
+        let mut s = Scheduler::default();
+        held_at(&mut s, deadline, 21);
+        held_at(&mut s, deadline, 20);
+        assert_eq!(s.timeout(), Some(Duration::ZERO));
+        assert_eq!(s.due(), [Pid::from_raw(20), Pid::from_raw(21)]);
+        assert_eq!(s.timeout(), None);
+    }
+}
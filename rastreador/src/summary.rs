@@ -0,0 +1,469 @@
+use clap::ValueEnum;
+use serde_json::json;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::arch::Abi;
+use crate::sysdecode::{error_name, syscall_name};
+
+// === Estadísticas por syscall y resumen final ===
+// Se acumulan en cada stop de salida (con o sin -v): llamadas, errores por errno y
+// tiempo entre los stops de entrada y salida. Las salidas cuya entrada no se vio
+// cuentan como llamada pero no aportan tiempo.
+
+const HIST_BUCKETS: usize = 32; // bucket 0: < 1 µs; bucket i: [2^(i-1), 2^i) µs
+const HIST_BAR: usize = 40; // ancho de la barra más larga
+
+/// Histograma de latencias con buckets log2 en microsegundos.
+#[derive(Debug, Default, Clone)]
+pub struct Histogram {
+    buckets: [u64; HIST_BUCKETS],
+    count: u64,
+    max: Duration,
+}
+
+impl Histogram {
+    pub fn record(&mut self, d: Duration) {
+        let us = d.as_micros() as u64;
+        let i = ((u64::BITS - us.leading_zeros()) as usize).min(HIST_BUCKETS - 1);
+        self.buckets[i] += 1;
+        self.count += 1;
+        self.max = self.max.max(d);
+    }
+
+    /// Rango [lo, hi) en µs del bucket `i`.
+    fn bucket_range(i: usize) -> (u64, u64) {
+        if i == 0 { (0, 1) } else { (1 << (i - 1), 1 << i) }
+    }
+
+    /// Percentil aproximado: límite superior del bucket que lo contiene (acotado por
+    /// el máximo observado).
+    pub fn percentile(&self, p: f64) -> Duration {
+        let target = ((self.count as f64) * p).ceil().max(1.0) as u64;
+        let mut acc = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            acc += n;
+            if acc >= target {
+                return Duration::from_micros(Self::bucket_range(i).1).min(self.max);
+            }
+        }
+        self.max
+    }
+
+    /// Barras ASCII de los buckets entre el primero y el último no vacíos.
+    fn print(&self, w: &mut dyn Write, title: &str) -> io::Result<()> {
+        let Some(first) = self.buckets.iter().position(|&n| n > 0) else {
+            return Ok(());
+        };
+        let last = self.buckets.iter().rposition(|&n| n > 0).unwrap_or(first);
+        let top = self.buckets.iter().copied().max().unwrap_or(1);
+        writeln!(w, "\n{} ({} llamadas, latencia en µs)", title, self.count)?;
+        for i in first..=last {
+            let (lo, hi) = Self::bucket_range(i);
+            let n = self.buckets[i];
+            let bar = "#".repeat(((n * HIST_BAR as u64).div_ceil(top)) as usize);
+            writeln!(w, "  [{:>8}, {:>8})  {:>8} |{:<width$}|", lo, hi, n, bar, width = HIST_BAR)?;
+        }
+        Ok(())
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let buckets: Vec<serde_json::Value> = self
+            .buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, &n)| n > 0)
+            .map(|(i, &n)| {
+                let (lo, hi) = Self::bucket_range(i);
+                json!({"lo_us": lo, "hi_us": hi, "count": n})
+            })
+            .collect();
+        json!(buckets)
+    }
+}
+
+/// Modo de --histogram: solo por syscall, o además por ruta del fd.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistogramBy {
+    Syscall,
+    Path,
+}
+
+#[derive(Debug, Default)]
+pub struct SyscallStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub timed: u64,        // llamadas con tiempo medido
+    pub total: Duration,   // suma de los tiempos medidos
+    pub min: Option<Duration>,
+    pub max: Duration,
+    pub errnos: BTreeMap<String, u64>, // nombre de errno → veces
+    pub injected: u64,     // llamadas alteradas por -e inject= (no cuentan como errores)
+    pub hist: Histogram,
+}
+
+/// Sentido de los bytes que mueve una syscall de E/S sobre uno de sus fds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoDir {
+    Read,
+    Write,
+}
+
+/// Fds de E/S de una syscall (índice del argumento y sentido). sendfile, splice y
+/// copy_file_range mueven los mismos bytes de un fd a otro: cuentan en ambos.
+pub fn io_fds(name: &str) -> &'static [(usize, IoDir)] {
+    match name {
+        "read" | "pread64" | "readv" | "preadv" | "preadv2" => &[(0, IoDir::Read)],
+        "write" | "pwrite64" | "writev" | "pwritev" | "pwritev2" => &[(0, IoDir::Write)],
+        "sendfile" | "sendfile64" => &[(1, IoDir::Read), (0, IoDir::Write)],
+        "copy_file_range" | "splice" => &[(0, IoDir::Read), (2, IoDir::Write)],
+        _ => &[],
+    }
+}
+
+/// Bytes, llamadas y tiempo de E/S sobre una ruta o extremo de socket (--io-report).
+#[derive(Debug, Default)]
+pub struct IoStats {
+    pub read: u64,
+    pub written: u64,
+    pub reads: u64,
+    pub writes: u64,
+    pub time: Duration,
+}
+
+impl IoStats {
+    fn bytes(&self) -> u64 {
+        self.read + self.written
+    }
+
+    fn calls(&self) -> u64 {
+        self.reads + self.writes
+    }
+}
+
+/// Estadísticas por (ABI, número de syscall) y, con --histogram=path, histogramas por
+/// ruta del fd sobre el que operó la syscall. Con --io-report, además, la E/S por
+/// destino del fd.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub syscalls: HashMap<(Abi, u64), SyscallStats>,
+    pub paths: BTreeMap<String, Histogram>,
+    pub histogram: Option<HistogramBy>,
+    pub io: HashMap<String, IoStats>,
+}
+
+impl Stats {
+    pub fn new(histogram: Option<HistogramBy>) -> Stats {
+        Stats { histogram, ..Default::default() }
+    }
+
+    /// Una syscall de E/S terminada sin error: `targets` son los destinos de sus fds,
+    /// resueltos en la entrada.
+    pub fn record_io(&mut self, targets: &[(IoDir, String)], duration: Option<Duration>, bytes: u64) {
+        for (dir, target) in targets {
+            let io = self.io.entry(target.clone()).or_default();
+            match dir {
+                IoDir::Read => {
+                    io.read += bytes;
+                    io.reads += 1;
+                }
+                IoDir::Write => {
+                    io.written += bytes;
+                    io.writes += 1;
+                }
+            }
+            io.time += duration.unwrap_or_default();
+        }
+    }
+
+    pub fn record(&mut self, key: (Abi, u64), duration: Option<Duration>, ret: i64, path: Option<String>, injected: bool) {
+        self.syscalls.entry(key).or_default().record(duration, ret, injected);
+        if let (Some(d), Some(path)) = (duration, path) {
+            self.paths.entry(path).or_default().record(d);
+        }
+    }
+}
+
+impl SyscallStats {
+    pub fn record(&mut self, duration: Option<Duration>, ret: i64, injected: bool) {
+        self.calls += 1;
+        if injected {
+            self.injected += 1;
+        } else if let Some(errno) = error_name(ret) {
+            self.errors += 1;
+            *self.errnos.entry(errno).or_insert(0) += 1;
+        }
+        if let Some(d) = duration {
+            self.timed += 1;
+            self.total += d;
+            self.min = Some(self.min.map_or(d, |m| m.min(d)));
+            self.max = self.max.max(d);
+            self.hist.record(d);
+        }
+    }
+
+    fn avg(&self) -> Duration {
+        if self.timed > 0 { self.total / self.timed as u32 } else { Duration::ZERO }
+    }
+}
+
+/// Orden de las filas del resumen (--summary-sort-by).
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    Time,
+    Calls,
+    Errors,
+    Name,
+}
+
+/// Filas ordenadas: nombre mostrado (con la ABI si no es la nativa), clave y estadísticas.
+fn sorted_rows(stats: &HashMap<(Abi, u64), SyscallStats>, sort: SortBy) -> Vec<(String, (Abi, u64), &SyscallStats)> {
+    let mut rows: Vec<_> = stats
+        .iter()
+        .map(|(&(abi, scno), s)| {
+            // Las syscalls de una ABI no nativa (p. ej. i386 en x86_64) se marcan aparte
+            let mut name = syscall_name(abi, scno);
+            if abi != Abi::native() {
+                name = format!("{name} [{}]", abi.name());
+            }
+            (name, (abi, scno), s)
+        })
+        .collect();
+    // Desempate por nombre para que el orden sea estable entre ejecuciones
+    rows.sort_by(|a, b| a.0.cmp(&b.0));
+    match sort {
+        SortBy::Time => rows.sort_by_key(|r| Reverse(r.2.total)),
+        SortBy::Calls => rows.sort_by_key(|r| Reverse(r.2.calls)),
+        SortBy::Errors => rows.sort_by_key(|r| Reverse(r.2.errors)),
+        SortBy::Name => {}
+    }
+    rows
+}
+
+fn secs(d: Duration) -> String {
+    format!("{:.6}", d.as_secs_f64())
+}
+
+pub fn print_summary(w: &mut dyn Write, stats: &Stats, sort: SortBy) -> io::Result<()> {
+    let syscalls = &stats.syscalls;
+    let total_calls: u64 = syscalls.values().map(|s| s.calls).sum();
+    let total_errors: u64 = syscalls.values().map(|s| s.errors).sum();
+    let total_time: Duration = syscalls.values().map(|s| s.total).sum();
+    let total_injected: u64 = syscalls.values().map(|s| s.injected).sum();
+    let rows = sorted_rows(syscalls, sort);
+    let hist = stats.histogram.is_some();
+    let inj = total_injected > 0;
+
+    writeln!(w, "\n===== RESUMEN DE SYSCALLS =====")?;
+    write!(
+        w,
+        "{:24}  {:>10}  {:>7}  {:>11}  {:>11}  {:>11}  {:>11}  {:>8}",
+        "Syscall", "Conteo", "%", "Total (s)", "Prom (s)", "Mín (s)", "Máx (s)", "Errores"
+    )?;
+    if inj {
+        write!(w, "  {:>8}", "Inyect.")?;
+    }
+    if hist {
+        write!(w, "  {:>11}  {:>11}  {:>11}", "p50 (s)", "p90 (s)", "p99 (s)")?;
+    }
+    writeln!(w)?;
+    write!(w, "{:-<24}  {:-<10}  {:-<7}  {:-<11}  {:-<11}  {:-<11}  {:-<11}  {:-<8}", "", "", "", "", "", "", "", "")?;
+    if inj {
+        write!(w, "  {:-<8}", "")?;
+    }
+    if hist {
+        write!(w, "  {:-<11}  {:-<11}  {:-<11}", "", "", "")?;
+    }
+    writeln!(w)?;
+    for (name, _, s) in &rows {
+        let pct = if total_calls > 0 { (s.calls as f64) * 100.0 / total_calls as f64 } else { 0.0 };
+        write!(
+            w,
+            "{:24}  {:>10}  {:>7.2}  {:>11}  {:>11}  {:>11}  {:>11}  {:>8}",
+            name,
+            s.calls,
+            pct,
+            secs(s.total),
+            secs(s.avg()),
+            secs(s.min.unwrap_or_default()),
+            secs(s.max),
+            s.errors
+        )?;
+        if inj {
+            write!(w, "  {:>8}", s.injected)?;
+        }
+        if hist {
+            let [p50, p90, p99] = [0.5, 0.9, 0.99].map(|p| secs(s.hist.percentile(p)));
+            write!(w, "  {:>11}  {:>11}  {:>11}", p50, p90, p99)?;
+        }
+        writeln!(w)?;
+    }
+    writeln!(w, "Total syscalls observadas: {}", total_calls)?;
+    writeln!(w, "Tiempo total en syscalls: {} s", secs(total_time))?;
+    writeln!(w, "Errores: {}", total_errors)?;
+    if inj {
+        writeln!(w, "Llamadas inyectadas: {}", total_injected)?;
+    }
+
+    // Desglose de errores por errno, en el mismo orden que la tabla
+    if total_errors > 0 {
+        writeln!(w, "\nErrores por syscall:")?;
+        for (name, _, s) in rows.iter().filter(|r| r.2.errors > 0) {
+            let errnos: Vec<String> = s.errnos.iter().map(|(e, n)| format!("{e}×{n}")).collect();
+            writeln!(w, "  {:24}  {}", name, errnos.join(", "))?;
+        }
+    }
+
+    if hist {
+        writeln!(w, "\n===== HISTOGRAMAS DE LATENCIA =====")?;
+        for (name, _, s) in &rows {
+            s.hist.print(w, name)?;
+        }
+        if !stats.paths.is_empty() {
+            writeln!(w, "\n----- Por ruta del fd -----")?;
+        }
+        for (path, h) in &stats.paths {
+            h.print(w, path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resumen de --format=jsonl: un último objeto `{"type":"summary",...}` para que la
+/// traza siga siendo JSON Lines válido aunque comparta archivo con los eventos (-o).
+pub fn print_summary_json(w: &mut dyn Write, stats: &Stats, sort: SortBy) -> io::Result<()> {
+    let total_calls: u64 = stats.syscalls.values().map(|s| s.calls).sum();
+    let total_errors: u64 = stats.syscalls.values().map(|s| s.errors).sum();
+    let total_time: Duration = stats.syscalls.values().map(|s| s.total).sum();
+    let syscalls: Vec<serde_json::Value> = sorted_rows(&stats.syscalls, sort)
+        .into_iter()
+        .map(|(_, (abi, scno), s)| {
+            let mut row = json!({
+                "abi": abi.name(),
+                "nr": scno,
+                "name": syscall_name(abi, scno),
+                "count": s.calls,
+                "pct": if total_calls > 0 { (s.calls as f64) * 100.0 / total_calls as f64 } else { 0.0 },
+                "seconds": s.total.as_secs_f64(),
+                "avg": s.avg().as_secs_f64(),
+                "min": s.min.map(|d| d.as_secs_f64()),
+                "max": s.max.as_secs_f64(),
+                "errors": s.errors,
+                "errnos": s.errnos,
+                "injected": s.injected,
+            });
+            if stats.histogram.is_some() {
+                row["p50"] = json!(s.hist.percentile(0.5).as_secs_f64());
+                row["p90"] = json!(s.hist.percentile(0.9).as_secs_f64());
+                row["p99"] = json!(s.hist.percentile(0.99).as_secs_f64());
+                row["histogram"] = s.hist.to_json();
+            }
+            row
+        })
+        .collect();
+    let mut summary = json!({
+        "type": "summary",
+        "total": total_calls,
+        "seconds": total_time.as_secs_f64(),
+        "errors": total_errors,
+        "syscalls": syscalls,
+    });
+    if stats.histogram == Some(HistogramBy::Path) {
+        let paths: serde_json::Map<String, serde_json::Value> =
+            stats.paths.iter().map(|(p, h)| (p.clone(), h.to_json())).collect();
+        summary["paths"] = json!(paths);
+    }
+    writeln!(w, "{}", summary)
+}
+
+/// Filas de --io-report ordenadas por bytes totales (desempate por nombre).
+fn io_rows(io: &HashMap<String, IoStats>) -> Vec<(&String, &IoStats)> {
+    let mut rows: Vec<_> = io.iter().collect();
+    rows.sort_by(|a, b| b.1.bytes().cmp(&a.1.bytes()).then_with(|| a.0.cmp(b.0)));
+    rows
+}
+
+/// Tabla de E/S por ruta o extremo de socket. La columna de bytes por llamada delata
+/// las escrituras pequeñas y frecuentes.
+pub fn print_io_report(w: &mut dyn Write, stats: &Stats) -> io::Result<()> {
+    writeln!(w, "\n===== E/S POR ARCHIVO =====")?;
+    writeln!(
+        w,
+        "{:>12}  {:>12}  {:>8}  {:>8}  {:>11}  {:>10}  Destino",
+        "Leídos", "Escritos", "Lect.", "Escr.", "Tiempo (s)", "Bytes/llam"
+    )?;
+    writeln!(w, "{:-<12}  {:-<12}  {:-<8}  {:-<8}  {:-<11}  {:-<10}  {:-<7}", "", "", "", "", "", "", "")?;
+    for (target, io) in io_rows(&stats.io) {
+        writeln!(
+            w,
+            "{:>12}  {:>12}  {:>8}  {:>8}  {:>11}  {:>10}  {}",
+            io.read,
+            io.written,
+            io.reads,
+            io.writes,
+            secs(io.time),
+            io.bytes() / io.calls().max(1),
+            target
+        )?;
+    }
+    Ok(())
+}
+
+/// Versión de --format=jsonl: un objeto `{"type":"io_report",...}` tras el resumen.
+pub fn print_io_report_json(w: &mut dyn Write, stats: &Stats) -> io::Result<()> {
+    let files: Vec<serde_json::Value> = io_rows(&stats.io)
+        .into_iter()
+        .map(|(target, io)| {
+            json!({
+                "target": target,
+                "read_bytes": io.read,
+                "written_bytes": io.written,
+                "reads": io.reads,
+                "writes": io.writes,
+                "seconds": io.time.as_secs_f64(),
+            })
+        })
+        .collect();
+    writeln!(w, "{}", json!({"type": "io_report", "files": files}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buckets_log2() {
+        let mut h = Histogram::default();
+        for us in [0, 1, 2, 3, 4, 1023, 1024] {
+            h.record(Duration::from_micros(us));
+        }
+        // [0,1) [1,2) [2,4)×2 [4,8) [512,1024) [1024,2048)
+        assert_eq!(&h.buckets[..5], &[1, 1, 2, 1, 0]);
+        assert_eq!((h.buckets[10], h.buckets[11]), (1, 1));
+        assert_eq!(Histogram::bucket_range(0), (0, 1));
+        assert_eq!(Histogram::bucket_range(11), (1024, 2048));
+    }
+
+    #[test]
+    fn percentiles() {
+        let mut h = Histogram::default();
+        for _ in 0..90 {
+            h.record(Duration::from_micros(3));
+        }
+        for _ in 0..10 {
+            h.record(Duration::from_micros(100));
+        }
+        // Límite superior del bucket, acotado por el máximo observado
+        assert_eq!(h.percentile(0.5), Duration::from_micros(4));
+        assert_eq!(h.percentile(0.9), Duration::from_micros(4));
+        assert_eq!(h.percentile(0.91), Duration::from_micros(100));
+        assert_eq!(h.percentile(0.99), Duration::from_micros(100));
+
+        let mut h = Histogram::default();
+        h.record(Duration::from_micros(5));
+        assert_eq!(h.percentile(0.5), Duration::from_micros(5));
+        assert_eq!(Histogram::default().percentile(0.99), Duration::ZERO);
+    }
+}
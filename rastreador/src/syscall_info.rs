@@ -0,0 +1,69 @@
+use nix::errno::Errno;
+use nix::unistd::Pid;
+use std::mem::{size_of, zeroed};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// === PTRACE_GET_SYSCALL_INFO (Linux >= 5.3) ===
+// Indica de forma autoritativa si un syscall-stop es de entrada, salida o seccomp,
+// sin depender de alternar un booleano por TID.
+
+// Valores de AUDIT_ARCH_* (linux/audit.h) que reporta el campo `arch`
+pub const AUDIT_ARCH_X86_64: u32 = 0xC000_003E;
+pub const AUDIT_ARCH_I386: u32 = 0x4000_0003;
+pub const AUDIT_ARCH_AARCH64: u32 = 0xC000_00B7;
+
+#[derive(Debug, Clone, Copy)]
+pub enum SyscallStop {
+    Entry { nr: u64, args: [u64; 6] },
+    Exit { rval: i64 },
+    Seccomp { nr: u64, args: [u64; 6] },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SyscallInfo {
+    pub stop: SyscallStop,
+    pub arch: u32,
+    pub instruction_pointer: u64,
+    pub stack_pointer: u64,
+}
+
+// Se apaga al primer EIO/EINVAL: kernel sin soporte, se usa el toggle de respaldo
+static SUPPORTED: AtomicBool = AtomicBool::new(true);
+
+/// Consulta PTRACE_GET_SYSCALL_INFO. Retorna None si el kernel no lo soporta o si el
+/// stop no es de syscall (op = NONE); el llamador debe recurrir al toggle.
+pub fn get_syscall_info(pid: Pid) -> Option<SyscallInfo> {
+    if !SUPPORTED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let mut raw: libc::ptrace_syscall_info = unsafe { zeroed() };
+    let res = unsafe {
+        libc::ptrace(
+            libc::PTRACE_GET_SYSCALL_INFO,
+            pid.as_raw(),
+            size_of::<libc::ptrace_syscall_info>(),
+            &mut raw as *mut libc::ptrace_syscall_info,
+        )
+    };
+    if res < 0 {
+        if matches!(Errno::last(), Errno::EIO | Errno::EINVAL) {
+            SUPPORTED.store(false, Ordering::Relaxed);
+        }
+        return None;
+    }
+
+    let stop = unsafe {
+        match raw.op {
+            libc::PTRACE_SYSCALL_INFO_ENTRY => SyscallStop::Entry { nr: raw.u.entry.nr, args: raw.u.entry.args },
+            libc::PTRACE_SYSCALL_INFO_EXIT => SyscallStop::Exit { rval: raw.u.exit.sval },
+            libc::PTRACE_SYSCALL_INFO_SECCOMP => SyscallStop::Seccomp { nr: raw.u.seccomp.nr, args: raw.u.seccomp.args },
+            _ => return None,
+        }
+    };
+    Some(SyscallInfo {
+        stop,
+        arch: raw.arch,
+        instruction_pointer: raw.instruction_pointer,
+        stack_pointer: raw.stack_pointer,
+    })
+}
@@ -1,272 +1,124 @@
-use libc;
+use crate::arch::Abi;
 use nix::sys::ptrace;
 use nix::unistd::Pid;
-use std::collections::HashMap;
 use std::io;
 use std::mem::size_of;
-use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-// === Nombres de syscalls: tabla parcial común en x86_64 Linux ===
-// Fallback: "sys_<num>" si no está en la tabla.
-pub fn syscall_name(n: u64) -> String {
-    static INIT: Lazy<HashMap<u64, &'static str>> = once_cell::sync::Lazy::new(|| {
-        use libc::*;
-        let mut m = HashMap::new();
-        // Núcleo
-        m.insert(SYS_read as u64, "read");
-        m.insert(SYS_write as u64, "write");
-        m.insert(SYS_open as u64, "open");
-        m.insert(SYS_openat as u64, "openat");
-        m.insert(SYS_close as u64, "close");
-        m.insert(SYS_statx as u64, "statx");
-        m.insert(SYS_fstat as u64, "fstat");
-        m.insert(SYS_lseek as u64, "lseek");
-        m.insert(SYS_mmap as u64, "mmap");
-        m.insert(SYS_munmap as u64, "munmap");
-        m.insert(SYS_brk as u64, "brk");
-        m.insert(SYS_mprotect as u64, "mprotect");
-        m.insert(SYS_rt_sigaction as u64, "rt_sigaction");
-        m.insert(SYS_rt_sigprocmask as u64, "rt_sigprocmask");
-        m.insert(SYS_rt_sigreturn as u64, "rt_sigreturn");
-        m.insert(SYS_clone as u64, "clone");
-        m.insert(SYS_fork as u64, "fork");
-        m.insert(SYS_vfork as u64, "vfork");
-        m.insert(SYS_execve as u64, "execve");
-        m.insert(SYS_exit as u64, "exit");
-        m.insert(SYS_exit_group as u64, "exit_group");
-        m.insert(SYS_wait4 as u64, "wait4");
-        m.insert(SYS_kill as u64, "kill");
-        m.insert(SYS_getpid as u64, "getpid");
-        m.insert(SYS_getppid as u64, "getppid");
-        m.insert(SYS_gettid as u64, "gettid");
-        m.insert(SYS_arch_prctl as u64, "arch_prctl");
-        m.insert(SYS_set_tid_address as u64, "set_tid_address");
-        m.insert(SYS_set_robust_list as u64, "set_robust_list");
-        m.insert(SYS_prlimit64 as u64, "prlimit64");
-        m.insert(SYS_clock_gettime as u64, "clock_gettime");
-        m.insert(SYS_clock_settime as u64, "clock_settime");
-        m.insert(SYS_clock_getres as u64, "clock_getres");
-        m.insert(SYS_clock_nanosleep as u64, "clock_nanosleep");
-        m.insert(SYS_nanosleep as u64, "nanosleep");
-        m.insert(SYS_gettimeofday as u64, "gettimeofday");
-        m.insert(SYS_settimeofday as u64, "settimeofday");
-        m.insert(SYS_time as u64, "time");
-        m.insert(SYS_times as u64, "times");
-        m.insert(SYS_getrandom as u64, "getrandom");
-        m.insert(SYS_rseq as u64, "rseq");
-        m.insert(SYS_uname as u64, "uname");
-        m.insert(SYS_getcwd as u64, "getcwd");
-        m.insert(SYS_chdir as u64, "chdir");
-        m.insert(SYS_fchdir as u64, "fchdir");
-        m.insert(SYS_umask as u64, "umask");
-        m.insert(SYS_getuid as u64, "getuid");
-        m.insert(SYS_geteuid as u64, "geteuid");
-        m.insert(SYS_getgid as u64, "getgid");
-        m.insert(SYS_getegid as u64, "getegid");
-        m.insert(SYS_setuid as u64, "setuid");
-        m.insert(SYS_setgid as u64, "setgid");
-        m.insert(SYS_setresuid as u64, "setresuid");
-        m.insert(SYS_getresuid as u64, "getresuid");
-        m.insert(SYS_setresgid as u64, "setresgid");
-        m.insert(SYS_getresgid as u64, "getresgid");
-        m.insert(SYS_setfsuid as u64, "setfsuid");
-        m.insert(SYS_setfsgid as u64, "setfsgid");
-        m.insert(SYS_personality as u64, "personality");
-        m.insert(SYS_getrusage as u64, "getrusage");
-        m.insert(SYS_sysinfo as u64, "sysinfo");
-        m.insert(SYS_prctl as u64, "prctl");
+mod classes; // clases de syscalls de strace (%file, %network, ...)
+mod fds; // anotación de descriptores con su destino (-y)
+mod flags; // tablas de flags/enumerados por familia
+mod format; // formateador genérico guiado por el esquema
+mod schema; // esquema declarativo de argumentos por syscall
+mod structs; // lectura y formato de structs del tracee
 
-        // Archivos y directorios
-        m.insert(SYS_access as u64, "access");
-        m.insert(SYS_faccessat as u64, "faccessat");
-        m.insert(SYS_readlink as u64, "readlink");
-        m.insert(SYS_readlinkat as u64, "readlinkat");
-        m.insert(SYS_link as u64, "link");
-        m.insert(SYS_linkat as u64, "linkat");
-        m.insert(SYS_symlink as u64, "symlink");
-        m.insert(SYS_symlinkat as u64, "symlinkat");
-        m.insert(SYS_unlink as u64, "unlink");
-        m.insert(SYS_unlinkat as u64, "unlinkat");
-        m.insert(SYS_mkdir as u64, "mkdir");
-        m.insert(SYS_mkdirat as u64, "mkdirat");
-        m.insert(SYS_rmdir as u64, "rmdir");
-        m.insert(SYS_mknod as u64, "mknod");
-        m.insert(SYS_mknodat as u64, "mknodat");
-        m.insert(SYS_rename as u64, "rename");
-        m.insert(SYS_renameat as u64, "renameat");
-        m.insert(SYS_chroot as u64, "chroot");
-        m.insert(SYS_truncate as u64, "truncate");
-        m.insert(SYS_ftruncate as u64, "ftruncate");
-        m.insert(SYS_chmod as u64, "chmod");
-        m.insert(SYS_fchmod as u64, "fchmod");
-        m.insert(SYS_fchmodat as u64, "fchmodat");
-        m.insert(SYS_chown as u64, "chown");
-        m.insert(SYS_fchown as u64, "fchown");
-        m.insert(SYS_lchown as u64, "lchown");
-        m.insert(SYS_fchownat as u64, "fchownat");
-        m.insert(SYS_utimensat as u64, "utimensat");
-        m.insert(SYS_getdents64 as u64, "getdents64");
-        m.insert(SYS_statfs as u64, "statfs");
-        m.insert(SYS_fstatfs as u64, "fstatfs");
-        m.insert(SYS_sync as u64, "sync");
-        m.insert(SYS_syncfs as u64, "syncfs");
-        m.insert(SYS_fsync as u64, "fsync");
-        m.insert(SYS_fdatasync as u64, "fdatasync");
-        m.insert(SYS_sync_file_range as u64, "sync_file_range");
-        m.insert(SYS_sendfile as u64, "sendfile");
+pub use classes::SyscallClass;
+pub use fds::{fd_args, returns_fd, FdCache};
+pub use format::{decode_args, decode_out_args, error_name, first_out_arg, format_return};
 
-        // I/O avanzadas
-        m.insert(SYS_pread64 as u64, "pread64");
-        m.insert(SYS_pwrite64 as u64, "pwrite64");
-        m.insert(SYS_preadv as u64, "preadv");
-        m.insert(SYS_pwritev as u64, "pwritev");
-        m.insert(SYS_splice as u64, "splice");
-        m.insert(SYS_tee as u64, "tee");
-        m.insert(SYS_vmsplice as u64, "vmsplice");
-        m.insert(SYS_copy_file_range as u64, "copy_file_range");
+// === Nombres de syscalls: tablas completas generadas por build.rs ===
+// Se generan a partir de los syscall_*.tbl del kernel vendorizados en syscalls/
+// (x86_64 con su rango x32, i386 y aarch64).
+include!(concat!(env!("OUT_DIR"), "/syscall_tables.rs"));
 
-        // Memoria
-        m.insert(SYS_madvise as u64, "madvise");
-        m.insert(SYS_mremap as u64, "mremap");
-        m.insert(SYS_mlock as u64, "mlock");
-        m.insert(SYS_munlock as u64, "munlock");
-        m.insert(SYS_mlockall as u64, "mlockall");
-        m.insert(SYS_munlockall as u64, "munlockall");
+// En la ABI x32 los números llevan este bit activo (__X32_SYSCALL_BIT); desde 512 están
+// las syscalls propias de x32, que sin el bit no existen en x86_64
+const X32_SYSCALL_BIT: u64 = 0x4000_0000;
+const X32_FIRST_OWN: u64 = 512;
 
-        // Planificación
-        m.insert(SYS_sched_yield as u64, "sched_yield");
-        m.insert(SYS_sched_setparam as u64, "sched_setparam");
-        m.insert(SYS_sched_getparam as u64, "sched_getparam");
-        m.insert(SYS_sched_setscheduler as u64, "sched_setscheduler");
-        m.insert(SYS_sched_getscheduler as u64, "sched_getscheduler");
-        m.insert(SYS_sched_get_priority_max as u64, "sched_get_priority_max");
-        m.insert(SYS_sched_get_priority_min as u64, "sched_get_priority_min");
-        m.insert(SYS_sched_rr_get_interval as u64, "sched_rr_get_interval");
-        m.insert(SYS_sched_setaffinity as u64, "sched_setaffinity");
-        m.insert(SYS_sched_getaffinity as u64, "sched_getaffinity");
+type NameTable = &'static [(u64, &'static str)];
+type NumberTable = &'static [(&'static str, u64)];
 
-        // Señales
-        m.insert(SYS_tgkill as u64, "tgkill");
-        m.insert(SYS_tkill as u64, "tkill");
-        m.insert(SYS_rt_sigsuspend as u64, "rt_sigsuspend");
-        m.insert(SYS_rt_sigpending as u64, "rt_sigpending");
-        m.insert(SYS_rt_sigtimedwait as u64, "rt_sigtimedwait");
-        m.insert(SYS_rt_sigqueueinfo as u64, "rt_sigqueueinfo");
-        m.insert(SYS_sigaltstack as u64, "sigaltstack");
-
-        // Redes
-        m.insert(SYS_socket as u64, "socket");
-        m.insert(SYS_socketpair as u64, "socketpair");
-        m.insert(SYS_connect as u64, "connect");
-        m.insert(SYS_bind as u64, "bind");
-        m.insert(SYS_listen as u64, "listen");
-        m.insert(SYS_accept as u64, "accept");
-        m.insert(SYS_accept4 as u64, "accept4");
-        m.insert(SYS_shutdown as u64, "shutdown");
-        m.insert(SYS_getsockname as u64, "getsockname");
-        m.insert(SYS_getpeername as u64, "getpeername");
-        m.insert(SYS_setsockopt as u64, "setsockopt");
-        m.insert(SYS_getsockopt as u64, "getsockopt");
-        m.insert(SYS_sendto as u64, "sendto");
-        m.insert(SYS_recvfrom as u64, "recvfrom");
-        m.insert(SYS_sendmsg as u64, "sendmsg");
-        m.insert(SYS_recvmsg as u64, "recvmsg");
-        m.insert(SYS_recvmmsg as u64, "recvmmsg");
-        m.insert(SYS_sendmmsg as u64, "sendmmsg");
-        m.insert(SYS_poll as u64, "poll");
-        m.insert(SYS_ppoll as u64, "ppoll");
-        m.insert(SYS_select as u64, "select");
-        m.insert(SYS_pselect6 as u64, "pselect6");
-
-        // epoll/inotify/eventfd/signalfd/timerfd
-        m.insert(SYS_epoll_create as u64, "epoll_create");
-        m.insert(SYS_epoll_create1 as u64, "epoll_create1");
-        m.insert(SYS_epoll_ctl as u64, "epoll_ctl");
-        m.insert(SYS_epoll_wait as u64, "epoll_wait");
-        m.insert(SYS_epoll_pwait as u64, "epoll_pwait");
-        m.insert(SYS_inotify_init as u64, "inotify_init");
-        m.insert(SYS_inotify_init1 as u64, "inotify_init1");
-        m.insert(SYS_inotify_add_watch as u64, "inotify_add_watch");
-        m.insert(SYS_inotify_rm_watch as u64, "inotify_rm_watch");
-        m.insert(SYS_eventfd as u64, "eventfd");
-        m.insert(SYS_eventfd2 as u64, "eventfd2");
-        m.insert(SYS_signalfd as u64, "signalfd");
-        m.insert(SYS_signalfd4 as u64, "signalfd4");
-        m.insert(SYS_timerfd_create as u64, "timerfd_create");
-        m.insert(SYS_timerfd_settime as u64, "timerfd_settime");
-        m.insert(SYS_timerfd_gettime as u64, "timerfd_gettime");
-
-        // Futex y atomics
-        m.insert(SYS_futex as u64, "futex");
-
-        // FS extendido
-        m.insert(SYS_getxattr as u64, "getxattr");
-        m.insert(SYS_lgetxattr as u64, "lgetxattr");
-        m.insert(SYS_fgetxattr as u64, "fgetxattr");
-        m.insert(SYS_setxattr as u64, "setxattr");
-        m.insert(SYS_lsetxattr as u64, "lsetxattr");
-        m.insert(SYS_fsetxattr as u64, "fsetxattr");
-        m.insert(SYS_listxattr as u64, "listxattr");
-        m.insert(SYS_llistxattr as u64, "llistxattr");
-        m.insert(SYS_flistxattr as u64, "flistxattr");
-        m.insert(SYS_removexattr as u64, "removexattr");
-        m.insert(SYS_lremovexattr as u64, "lremovexattr");
-        m.insert(SYS_fremovexattr as u64, "fremovexattr");
-
-        // Montaje y namespaces
-        m.insert(SYS_mount as u64, "mount");
-        m.insert(SYS_umount2 as u64, "umount2");
-        m.insert(SYS_pivot_root as u64, "pivot_root");
-        m.insert(SYS_unshare as u64, "unshare");
-        m.insert(SYS_setns as u64, "setns");
+fn tables(abi: Abi) -> (NameTable, NumberTable) {
+    match abi {
+        Abi::X86_64 => (X86_64_NAMES, X86_64_NUMBERS),
+        Abi::I386 => (I386_NAMES, I386_NUMBERS),
+        Abi::Aarch64 => (AARCH64_NAMES, AARCH64_NUMBERS),
+    }
+}
 
-        // Varios
-        m.insert(SYS_readahead as u64, "readahead");
-        m.insert(SYS_fanotify_init as u64, "fanotify_init");
-        m.insert(SYS_fanotify_mark as u64, "fanotify_mark");
-        m.insert(SYS_memfd_create as u64, "memfd_create");
-        m.insert(SYS_name_to_handle_at as u64, "name_to_handle_at");
-        m.insert(SYS_open_by_handle_at as u64, "open_by_handle_at");
-        m.insert(SYS_perf_event_open as u64, "perf_event_open");
-        m.insert(SYS_bpf as u64, "bpf");
+/// Número → nombre de syscall según la ABI (en x86_64, el rango x32 512-547 solo con
+/// __X32_SYSCALL_BIT). Fallback: "sys_<num>" si no está en la tabla.
+pub fn syscall_name(abi: Abi, n: u64) -> String {
+    let nr = if abi == Abi::X86_64 { n & !X32_SYSCALL_BIT } else { n };
+    let native_x32_nr = abi == Abi::X86_64 && n & X32_SYSCALL_BIT == 0 && nr >= X32_FIRST_OWN;
+    let (names, _) = tables(abi);
+    match names.binary_search_by_key(&nr, |&(k, _)| k) {
+        Ok(i) if !native_x32_nr => names[i].1.to_string(),
+        _ => format!("sys_{}", n),
+    }
+}
 
-        m
-    });
+/// Nombre → número de syscall en la ABI dada (búsqueda inversa de la misma tabla).
+pub fn syscall_number(abi: Abi, name: &str) -> Option<u64> {
+    let (_, numbers) = tables(abi);
+    numbers.binary_search_by(|&(k, _)| k.cmp(name)).ok().map(|i| numbers[i].1)
+}
 
-    if let Some(&name) = INIT.get(&n) {
-        name.to_string()
-    } else {
-        format!("sys_{}", n)
-    }
+/// ¿Es `name` una syscall de alguna de las ABIs soportadas?
+pub fn is_syscall_name(name: &str) -> bool {
+    [Abi::X86_64, Abi::I386, Abi::Aarch64].iter().any(|&abi| syscall_number(abi, name).is_some())
 }
 
 // === Lectura segura de memoria del hijo ===
 const MAX_STR: usize = 4096; // límite al leer C-strings
 
-pub fn read_c_string(pid: Pid, addr: u64) -> io::Result<String> {
-    if addr == 0 { return Ok(String::from("NULL")); }
+/// Lee una C-string del tracee tal cual (sin convertir a UTF-8: las rutas pueden
+/// tener cualquier byte y el escapado debe mostrar los originales).
+pub fn read_c_string(pid: Pid, addr: u64) -> io::Result<Vec<u8>> {
     let word_size = size_of::<usize>();
     let mut bytes = Vec::<u8>::with_capacity(64);
     let mut p = addr;
     loop {
         let data = ptrace::read(pid, p as ptrace::AddressType)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "ptrace::read"))? as usize;
+            .map_err(|_| io::Error::other("ptrace::read"))? as usize;
         let chunk = data.to_ne_bytes();
         for &b in &chunk {
-            if b == 0 { return Ok(String::from_utf8_lossy(&bytes).into()); }
+            if b == 0 { return Ok(bytes); }
             bytes.push(b);
-            if bytes.len() >= MAX_STR { return Ok(String::from_utf8_lossy(&bytes).into()); }
+            if bytes.len() >= MAX_STR { return Ok(bytes); }
         }
         p = p.wrapping_add(word_size as u64);
     }
 }
 
+/// Lee hasta `len` bytes desde `addr` (palabra a palabra, como read_c_string).
+pub fn read_bytes(pid: Pid, addr: u64, len: usize) -> io::Result<Vec<u8>> {
+    let word_size = size_of::<usize>();
+    let mut bytes = Vec::<u8>::with_capacity(len + word_size);
+    let mut p = addr;
+    while bytes.len() < len {
+        let data = ptrace::read(pid, p as ptrace::AddressType)
+            .map_err(|_| io::Error::other("ptrace::read"))? as usize;
+        bytes.extend_from_slice(&data.to_ne_bytes());
+        p = p.wrapping_add(word_size as u64);
+    }
+    bytes.truncate(len);
+    Ok(bytes)
+}
+
 pub fn read_ptr(pid: Pid, addr: u64) -> io::Result<usize> {
     let data = ptrace::read(pid, addr as ptrace::AddressType)
-        .map_err(|_| io::Error::new(io::ErrorKind::Other, "ptrace::read"))? as usize;
+        .map_err(|_| io::Error::other("ptrace::read"))? as usize;
     Ok(data)
 }
 
+/// Escribe `data` en `addr` del tracee, palabra a palabra como read_ptr (la última
+/// palabra se completa con lo que ya había en memoria).
+pub fn write_memory(pid: Pid, addr: u64, data: &[u8]) -> io::Result<()> {
+    let word_size = size_of::<usize>();
+    for (i, chunk) in data.chunks(word_size).enumerate() {
+        let p = addr.wrapping_add((i * word_size) as u64);
+        let mut word = if chunk.len() < word_size { read_ptr(pid, p)? } else { 0 }.to_ne_bytes();
+        word[..chunk.len()].copy_from_slice(chunk);
+        let value = usize::from_ne_bytes(word);
+        // SAFETY: PTRACE_POKEDATA no desreferencia `data`: es la palabra a escribir
+        unsafe { ptrace::write(pid, p as ptrace::AddressType, value as *mut libc::c_void) }
+            .map_err(|_| io::Error::other("ptrace::write"))?;
+    }
+    Ok(())
+}
+
 // === Decodificación de errno ===
 pub fn decode_errno(errno: i32) -> String {
     // Lista común; si no coincide, devuelve "EPERM(1)" estilo genérico
@@ -314,30 +166,241 @@ pub fn decode_errno(errno: i32) -> String {
         libc::EIDRM => "EIDRM".into(),
         libc::EILSEQ => "EILSEQ".into(),
         libc::EOVERFLOW => "EOVERFLOW".into(),
-        _ => format!("ERR({})", errno),
+        // El resto, con el nombre que conoce nix (strace los nombra todos)
+        _ => match nix::errno::Errno::from_i32(errno) {
+            nix::errno::Errno::UnknownErrno => format!("ERR({})", errno),
+            e => format!("{:?}", e),
+        },
     }
 }
 
-// === Flags de open/openat (parcial) ===
-pub fn fmt_flags_open(flags: i32) -> String {
-    use libc::*;
+/// Descripción de un errno como la da strerror(3): `No such file or directory`.
+pub fn errno_desc(errno: i32) -> &'static str {
+    nix::errno::Errno::from_i32(errno).desc()
+}
+
+// === Rutas y descriptores ===
+/// Índice del primer argumento de tipo fd de la syscall según su esquema.
+pub fn fd_arg(abi: Abi, name: &str) -> Option<usize> {
+    schema::syscall_spec(abi, name)?.args.iter().position(|&(_, ty)| matches!(ty, schema::ArgType::Fd))
+}
+
+/// Destino de un fd del tracee según /proc/<pid>/fd (ruta, `socket:[ino]`, `pipe:[ino]`...).
+pub fn fd_path(pid: Pid, fd: i32) -> Option<String> {
+    let target = std::fs::read_link(format!("/proc/{}/fd/{}", pid, fd)).ok()?;
+    Some(target.to_string_lossy().into_owned())
+}
+
+/// Rutas a las que se refiere una syscall en su entrada: argumentos ruta (las relativas
+/// se completan con su dirfd o con el cwd del tracee) y destinos de sus fds. Para -P.
+pub fn referenced_paths(pid: Pid, abi: Abi, name: &str, args: &[u64; 6]) -> Vec<String> {
+    let Some(spec) = schema::syscall_spec(abi, name) else {
+        return Vec::new();
+    };
+    let mut paths = Vec::new();
+    let mut base: Option<String> = None; // directorio del último dirfd visto
+    for (i, &(_, ty)) in spec.args.iter().enumerate() {
+        let v = if abi.pointer_size() == 4 { args[i] as u32 as u64 } else { args[i] };
+        match ty {
+            schema::ArgType::Fd => paths.extend(fd_path(pid, v as i32)),
+            schema::ArgType::DirFd if v as i32 == libc::AT_FDCWD => base = None,
+            schema::ArgType::DirFd => {
+                base = fd_path(pid, v as i32);
+                paths.extend(base.clone());
+            }
+            schema::ArgType::Path if v != 0 => {
+                let Ok(path) = read_c_string(pid, v) else { continue };
+                let path = String::from_utf8_lossy(&path).into_owned();
+                if path.starts_with('/') {
+                    paths.push(clean_path(&path));
+                } else {
+                    let dir = base.clone().or_else(|| {
+                        std::fs::read_link(format!("/proc/{}/cwd", pid)).ok().map(|p| p.to_string_lossy().into_owned())
+                    });
+                    match dir {
+                        Some(dir) => paths.push(clean_path(&format!("{}/{}", dir, path))),
+                        None => paths.push(path),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    paths
+}
+
+/// Normaliza una ruta absoluta quitando `.`, `..` y barras repetidas (sin seguir enlaces).
+fn clean_path(path: &str) -> String {
     let mut parts: Vec<&str> = Vec::new();
-    // modo acceso
-    match flags & O_ACCMODE {
-        O_RDONLY => parts.push("O_RDONLY"),
-        O_WRONLY => parts.push("O_WRONLY"),
-        O_RDWR => parts.push("O_RDWR"),
-        _ => {}
+    for comp in path.split('/') {
+        match comp {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            c => parts.push(c),
+        }
+    }
+    format!("/{}", parts.join("/"))
+}
+
+// === Señales ===
+pub fn signal_name(sig: i32) -> String {
+    match nix::sys::signal::Signal::try_from(sig) {
+        Ok(s) => s.as_str().to_string(),
+        // Señales de tiempo real según el kernel (32..64; glibc reserva las dos primeras)
+        Err(_) if (32..=64).contains(&sig) => format!("SIGRT_{}", sig - 32),
+        Err(_) => format!("{}", sig),
+    }
+}
+
+/// Nombre del si_code de una señal como lo muestra strace (`SI_USER`, `CLD_EXITED`,
+/// `SEGV_MAPERR`…); los códigos desconocidos van en decimal.
+fn si_code_name(sig: i32, code: i32) -> String {
+    let name = match (sig, code) {
+        (_, libc::SI_USER) => "SI_USER",
+        (_, libc::SI_KERNEL) => "SI_KERNEL",
+        (_, libc::SI_QUEUE) => "SI_QUEUE",
+        (_, libc::SI_TIMER) => "SI_TIMER",
+        (_, libc::SI_MESGQ) => "SI_MESGQ",
+        (_, libc::SI_ASYNCIO) => "SI_ASYNCIO",
+        (_, libc::SI_SIGIO) => "SI_SIGIO",
+        (_, libc::SI_TKILL) => "SI_TKILL",
+        (libc::SIGCHLD, 1) => "CLD_EXITED",
+        (libc::SIGCHLD, 2) => "CLD_KILLED",
+        (libc::SIGCHLD, 3) => "CLD_DUMPED",
+        (libc::SIGCHLD, 4) => "CLD_TRAPPED",
+        (libc::SIGCHLD, 5) => "CLD_STOPPED",
+        (libc::SIGCHLD, 6) => "CLD_CONTINUED",
+        (libc::SIGSEGV, 1) => "SEGV_MAPERR",
+        (libc::SIGSEGV, 2) => "SEGV_ACCERR",
+        (libc::SIGBUS, 1) => "BUS_ADRALN",
+        (libc::SIGBUS, 2) => "BUS_ADRERR",
+        (libc::SIGBUS, 3) => "BUS_OBJERR",
+        (libc::SIGFPE, 1) => "FPE_INTDIV",
+        (libc::SIGFPE, 2) => "FPE_INTOVF",
+        (libc::SIGFPE, 3) => "FPE_FLTDIV",
+        (libc::SIGILL, 1) => "ILL_ILLOPC",
+        (libc::SIGILL, 2) => "ILL_ILLOPN",
+        (libc::SIGTRAP, 1) => "TRAP_BRKPT",
+        (libc::SIGTRAP, 2) => "TRAP_TRACE",
+        _ => return code.to_string(),
+    };
+    name.to_string()
+}
+
+/// siginfo de una entrega de señal en la sintaxis de strace:
+/// `{si_signo=SIGCHLD, si_code=CLD_EXITED, si_pid=42, si_uid=1000, si_status=0, ...}`.
+pub fn fmt_siginfo(info: &libc::siginfo_t) -> String {
+    let (sig, code) = (info.si_signo, info.si_code);
+    let mut out = format!("{{si_signo={}, si_code={}", signal_name(sig), si_code_name(sig, code));
+    // SAFETY: cada campo de la unión se lee solo para los si_code que lo llenan
+    unsafe {
+        match sig {
+            libc::SIGCHLD if code > 0 => {
+                let status = if code == 1 { info.si_status().to_string() } else { signal_name(info.si_status()) };
+                out.push_str(&format!(
+                    ", si_pid={}, si_uid={}, si_status={}, si_utime={}, si_stime={}",
+                    info.si_pid(),
+                    info.si_uid(),
+                    status,
+                    info.si_utime(),
+                    info.si_stime()
+                ));
+            }
+            libc::SIGSEGV | libc::SIGBUS | libc::SIGFPE | libc::SIGILL | libc::SIGTRAP if code > 0 => {
+                let addr = info.si_addr() as u64;
+                if addr == 0 {
+                    out.push_str(", si_addr=NULL");
+                } else {
+                    out.push_str(&format!(", si_addr=0x{:x}", addr));
+                }
+            }
+            _ if code == libc::SI_USER || code == libc::SI_TKILL || code == libc::SI_QUEUE => {
+                out.push_str(&format!(", si_pid={}, si_uid={}", info.si_pid(), info.si_uid()));
+            }
+            _ => {}
+        }
+    }
+    out.push('}');
+    out
+}
+
+// === Escapado estilo C para cadenas y buffers ===
+// Por defecto los bytes no imprimibles van como \xNN; con --strace-compat se usan las
+// reglas de strace (octal mínimo, o de 3 dígitos si le sigue un dígito octal, y \v \f)
+// para que los analizadores de logs de strace lean igual las cadenas.
+static STRACE_QUOTING: AtomicBool = AtomicBool::new(false);
+
+pub fn set_strace_quoting(on: bool) {
+    STRACE_QUOTING.store(on, Ordering::Relaxed);
+}
+
+pub fn escape_bytes(bytes: &[u8]) -> String {
+    let strace = STRACE_QUOTING.load(Ordering::Relaxed);
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('"');
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            b'\r' => out.push_str("\\r"),
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x0b if strace => out.push_str("\\v"),
+            0x0c if strace => out.push_str("\\f"),
+            0x20..=0x7e => out.push(b as char),
+            _ if strace => {
+                let next_is_octal = matches!(bytes.get(i + 1), Some(b'0'..=b'7'));
+                if next_is_octal {
+                    out.push_str(&format!("\\{:03o}", b));
+                } else {
+                    out.push_str(&format!("\\{:o}", b));
+                }
+            }
+            _ => out.push_str(&format!("\\x{:02x}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nombres_x32() {
+        assert_eq!(syscall_name(Abi::X86_64, 0), "read");
+        assert_eq!(syscall_name(Abi::X86_64, X32_SYSCALL_BIT), "read");
+        assert_eq!(syscall_name(Abi::X86_64, X32_SYSCALL_BIT | 512), "rt_sigaction");
+        // Sin el bit, 512-547 es un número inválido en x86_64 (ENOSYS)
+        assert_eq!(syscall_name(Abi::X86_64, 512), "sys_512");
+        assert_eq!(syscall_name(Abi::X86_64, 547), "sys_547");
+    }
+
+    #[test]
+    fn nombres_de_si_code() {
+        assert_eq!(si_code_name(libc::SIGINT, libc::SI_USER), "SI_USER");
+        assert_eq!(si_code_name(libc::SIGUSR1, libc::SI_TKILL), "SI_TKILL");
+        assert_eq!(si_code_name(libc::SIGCHLD, 1), "CLD_EXITED");
+        assert_eq!(si_code_name(libc::SIGSEGV, 1), "SEGV_MAPERR");
+        // Los códigos propios de una señal no valen para otra
+        assert_eq!(si_code_name(libc::SIGUSR1, 1), "1");
+    }
+
+    // Un solo test para los dos modos: el modo de strace es global
+    #[test]
+    fn escapado_propio_y_de_strace() {
+        let bytes = b"a\"\\\n\t\r\x0b\x0c\x00\x7f\xff\x001";
+        set_strace_quoting(false);
+        assert_eq!(escape_bytes(bytes), r#""a\"\\\n\t\r\x0b\x0c\x00\x7f\xff\x001""#);
+        set_strace_quoting(true);
+        // Octal mínimo, o de tres dígitos si le sigue un dígito octal
+        assert_eq!(escape_bytes(bytes), r#""a\"\\\n\t\r\v\f\0\177\377\0001""#);
+        assert_eq!(escape_bytes(b"caf\xe9"), r#""caf\351""#);
+        assert_eq!(escape_bytes(b"\x018"), r#""\18""#);
+        set_strace_quoting(false);
+        assert_eq!(escape_bytes(b""), r#""""#);
     }
-    // otros
-    if flags & O_CREAT != 0 { parts.push("O_CREAT"); }
-    if flags & O_TRUNC != 0 { parts.push("O_TRUNC"); }
-    if flags & O_APPEND != 0 { parts.push("O_APPEND"); }
-    if flags & O_CLOEXEC != 0 { parts.push("O_CLOEXEC"); }
-    if flags & O_EXCL != 0 { parts.push("O_EXCL"); }
-    if flags & O_DIRECTORY != 0 { parts.push("O_DIRECTORY"); }
-    if flags & O_NOFOLLOW != 0 { parts.push("O_NOFOLLOW"); }
-    if flags & O_NONBLOCK != 0 { parts.push("O_NONBLOCK"); }
-    if parts.is_empty() { return format!("0x{:x}", flags); }
-    parts.join("|")
 }
@@ -0,0 +1,89 @@
+use crate::arch::Abi;
+
+use super::schema::{syscall_spec, ArgType, RetType};
+
+// === Clases de syscalls (%file, %network, ...) ===
+// Las clases de strace para -e trace=. %file y %desc se deducen del esquema (argumentos
+// de tipo ruta o fd); las demás son listas explícitas por nombre, que cubren también
+// las variantes de i386 (socketcall, ipc, sigaction, mmap2...).
+
+/// Clase de syscalls de strace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallClass {
+    File,    // reciben una ruta
+    Desc,    // reciben o devuelven un descriptor
+    Network, // sockets
+    Process, // gestión de procesos
+    Signal,  // señales
+    Memory,  // mapeo de memoria
+    Ipc,     // IPC de System V
+}
+
+const NETWORK: &[&str] = &[
+    "socket", "socketpair", "bind", "listen", "connect", "accept", "accept4", "getsockname", "getpeername",
+    "sendto", "recvfrom", "sendmsg", "recvmsg", "sendmmsg", "recvmmsg", "recvmmsg_time64", "shutdown",
+    "setsockopt", "getsockopt", "socketcall", "send", "recv",
+];
+
+const PROCESS: &[&str] = &[
+    "fork", "vfork", "clone", "clone3", "execve", "execveat", "exit", "exit_group", "wait4", "waitid",
+    "waitpid", "kill", "tkill", "tgkill", "rt_sigqueueinfo", "rt_tgsigqueueinfo", "pidfd_open",
+    "pidfd_send_signal", "unshare",
+];
+
+const SIGNAL: &[&str] = &[
+    "rt_sigaction", "sigaction", "signal", "rt_sigprocmask", "sigprocmask", "rt_sigreturn", "sigreturn",
+    "rt_sigsuspend", "sigsuspend", "rt_sigpending", "sigpending", "rt_sigtimedwait", "rt_sigtimedwait_time64",
+    "rt_sigqueueinfo", "rt_tgsigqueueinfo", "sigaltstack", "kill", "tkill", "tgkill", "pause", "alarm",
+    "signalfd", "signalfd4", "pidfd_send_signal", "sgetmask", "ssetmask",
+];
+
+const MEMORY: &[&str] = &[
+    "brk", "mmap", "mmap2", "old_mmap", "munmap", "mprotect", "pkey_mprotect", "mremap", "madvise",
+    "process_madvise", "mlock", "mlock2", "munlock", "mlockall", "munlockall", "msync", "mincore",
+    "remap_file_pages", "mbind", "set_mempolicy", "get_mempolicy", "migrate_pages", "move_pages",
+    "process_mrelease",
+];
+
+const IPC: &[&str] = &[
+    "msgget", "msgsnd", "msgrcv", "msgctl", "semget", "semop", "semtimedop", "semtimedop_time64", "semctl",
+    "shmget", "shmat", "shmdt", "shmctl", "ipc",
+];
+
+impl SyscallClass {
+    /// Clase a partir de su nombre en -e trace= (sin el `%`).
+    pub fn from_name(name: &str) -> Option<SyscallClass> {
+        Some(match name {
+            "file" => SyscallClass::File,
+            "desc" => SyscallClass::Desc,
+            "network" | "net" => SyscallClass::Network,
+            "process" => SyscallClass::Process,
+            "signal" => SyscallClass::Signal,
+            "memory" => SyscallClass::Memory,
+            "ipc" => SyscallClass::Ipc,
+            _ => return None,
+        })
+    }
+
+    /// ¿Pertenece la syscall `name` a esta clase?
+    pub fn contains(self, abi: Abi, name: &str) -> bool {
+        let list = match self {
+            SyscallClass::File => return has_arg(abi, name, |ty| ty == ArgType::Path),
+            SyscallClass::Desc => {
+                let returns_fd = syscall_spec(abi, name).is_some_and(|s| s.ret == RetType::Fd);
+                return returns_fd
+                    || has_arg(abi, name, |ty| matches!(ty, ArgType::Fd | ArgType::DirFd | ArgType::Pollfds(_)));
+            }
+            SyscallClass::Network => NETWORK,
+            SyscallClass::Process => PROCESS,
+            SyscallClass::Signal => SIGNAL,
+            SyscallClass::Memory => MEMORY,
+            SyscallClass::Ipc => IPC,
+        };
+        list.contains(&name)
+    }
+}
+
+fn has_arg(abi: Abi, name: &str, pred: impl Fn(ArgType) -> bool) -> bool {
+    syscall_spec(abi, name).is_some_and(|s| s.args.iter().any(|&(_, ty)| pred(ty)))
+}
@@ -0,0 +1,164 @@
+use crate::arch::Abi;
+use nix::unistd::Pid;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::fd_path;
+use super::schema::{syscall_spec, ArgType, RetType};
+
+// === Anotación de descriptores (-y) ===
+// Cada fd se muestra con su destino: `3</etc/passwd>`, `5<UNIX:[12345]>`,
+// `6<TCP:127.0.0.1:8080->127.0.0.1:4321>`. El destino sale de /proc/PID/fd y, para
+// sockets, del inodo buscado en /proc/PID/net/* (tablas del namespace de red del
+// tracee). Todo se lee bajo demanda y se cachea por proceso: el enlace de /proc/PID/fd
+// se relee siempre (es barato) y solo si cambió se vuelven a recorrer las tablas.
+
+/// Syscalls que cambian los extremos de un socket ya creado: tras ellas hay que
+/// volver a resolver su fd.
+const REBINDS: &[&str] = &["bind", "connect", "listen", "accept", "accept4"];
+
+/// Destinos de los fds ya resueltos, por TGID (los hilos comparten la tabla de fds).
+#[derive(Debug, Default)]
+pub struct FdCache {
+    procs: HashMap<i32, HashMap<i32, (String, String)>>, // fd → (enlace, descripción)
+}
+
+impl FdCache {
+    /// Descripción del fd `fd` del hilo `tid` (proceso `tgid`), o None si no existe.
+    pub fn describe(&mut self, tid: Pid, tgid: i32, fd: i32) -> Option<String> {
+        let link = fd_path(tid, fd)?;
+        let fds = self.procs.entry(tgid).or_default();
+        if let Some((cached, desc)) = fds.get(&fd) {
+            if *cached == link {
+                return Some(desc.clone());
+            }
+        }
+        let desc = match socket_inode(&link) {
+            Some(ino) => socket_endpoint(tid, ino).unwrap_or_else(|| link.clone()),
+            None => link.clone(),
+        };
+        fds.insert(fd, (link, desc.clone()));
+        Some(desc)
+    }
+
+    /// Olvida el fd de una syscall que cambia sus extremos (bind, connect...). Se llama
+    /// en la salida, con los argumentos de la entrada.
+    pub fn invalidate(&mut self, tgid: i32, name: &str, args: &[u64; 6]) {
+        if REBINDS.contains(&name) {
+            if let Some(fds) = self.procs.get_mut(&tgid) {
+                fds.remove(&(args[0] as i32));
+            }
+        }
+    }
+
+    /// El proceso terminó: sus fds ya no existen.
+    pub fn forget_process(&mut self, tgid: i32) {
+        self.procs.remove(&tgid);
+    }
+}
+
+/// Índices de los argumentos fd (incluidos los dirfd) de una syscall según el esquema.
+pub fn fd_args(abi: Abi, name: &str) -> Vec<usize> {
+    syscall_spec(abi, name).map_or_else(Vec::new, |s| {
+        s.args.iter().enumerate().filter(|(_, &(_, ty))| matches!(ty, ArgType::Fd | ArgType::DirFd)).map(|(i, _)| i).collect()
+    })
+}
+
+/// ¿Devuelve la syscall un descriptor nuevo?
+pub fn returns_fd(abi: Abi, name: &str) -> bool {
+    syscall_spec(abi, name).is_some_and(|s| s.ret == RetType::Fd)
+}
+
+fn socket_inode(link: &str) -> Option<u64> {
+    link.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+}
+
+/// Extremos del socket con inodo `ino` según las tablas de /proc/PID/net, en el orden
+/// en que es más probable encontrarlo.
+fn socket_endpoint(pid: Pid, ino: u64) -> Option<String> {
+    for (table, proto) in [("tcp", "TCP"), ("tcp6", "TCPv6"), ("udp", "UDP"), ("udp6", "UDPv6")] {
+        if let Some(desc) = inet_endpoint(pid, table, proto, ino) {
+            return Some(desc);
+        }
+    }
+    unix_endpoint(pid, ino)
+}
+
+/// Línea de /proc/net/{tcp,udp}[6]: `sl local rem st ... uid timeout inode ...`.
+fn inet_endpoint(pid: Pid, table: &str, proto: &str, ino: u64) -> Option<String> {
+    let text = std::fs::read_to_string(format!("/proc/{}/net/{}", pid, table)).ok()?;
+    let fields: Vec<&str> = text
+        .lines()
+        .skip(1)
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+        .find(|f| f.get(9).and_then(|i| i.parse::<u64>().ok()) == Some(ino))?;
+    let local = inet_addr(fields[1])?;
+    let remote = inet_addr(fields[2])?;
+    // Sin conectar (escucha o UDP sin connect) el remoto es todo ceros
+    if fields[2].bytes().all(|b| b == b'0' || b == b':') {
+        Some(format!("{}:{}", proto, local))
+    } else {
+        Some(format!("{}:{}->{}", proto, local, remote))
+    }
+}
+
+/// `0100007F:1F90` → `127.0.0.1:8080`. La dirección está en palabras de 32 bits en el
+/// orden de bytes del host; el puerto, en hex normal.
+fn inet_addr(field: &str) -> Option<String> {
+    let (addr, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for i in (0..addr.len()).step_by(8) {
+        let word = u32::from_str_radix(addr.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    match bytes.len() {
+        4 => Some(format!("{}:{}", Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]), port)),
+        16 => {
+            let octets: [u8; 16] = bytes.try_into().ok()?;
+            Some(format!("[{}]:{}", Ipv6Addr::from(octets), port))
+        }
+        _ => None,
+    }
+}
+
+/// Línea de /proc/net/unix: `Num RefCount Protocol Flags Type St Inode [Path]`.
+fn unix_endpoint(pid: Pid, ino: u64) -> Option<String> {
+    let text = std::fs::read_to_string(format!("/proc/{}/net/unix", pid)).ok()?;
+    let fields: Vec<&str> = text
+        .lines()
+        .skip(1)
+        .map(|l| l.split_whitespace().collect::<Vec<_>>())
+        .find(|f| f.get(6).and_then(|i| i.parse::<u64>().ok()) == Some(ino))?;
+    match fields.get(7) {
+        Some(path) => Some(format!("UNIX:[{},{}]", ino, path)),
+        None => Some(format!("UNIX:[{}]", ino)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direcciones_de_proc_net() {
+        // Palabras de 32 bits en el orden de bytes del host
+        let v4 = u32::from_ne_bytes([127, 0, 0, 1]);
+        assert_eq!(inet_addr(&format!("{:08X}:1F90", v4)).unwrap(), "127.0.0.1:8080");
+        assert_eq!(inet_addr("00000000:0000").unwrap(), "0.0.0.0:0");
+
+        let loopback6 = format!("{}{:08X}:0050", "0".repeat(24), u32::from_ne_bytes([0, 0, 0, 1]));
+        assert_eq!(inet_addr(&loopback6).unwrap(), "[::1]:80");
+
+        assert!(inet_addr("0100007F").is_none());
+        assert!(inet_addr("0100007F:ZZZZ").is_none());
+        assert!(inet_addr("0100:0050").is_none());
+    }
+
+    #[test]
+    fn inodos_de_socket() {
+        assert_eq!(socket_inode("socket:[12345]"), Some(12345));
+        assert_eq!(socket_inode("pipe:[12345]"), None);
+        assert_eq!(socket_inode("/etc/passwd"), None);
+    }
+}
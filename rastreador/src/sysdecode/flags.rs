@@ -0,0 +1,323 @@
+use super::schema::FlagKind;
+
+// === Flags y valores enumerados ===
+// Tablas (valor, nombre) por familia. Las de bits se combinan con '|' y lo que sobra
+// se muestra en hexadecimal; las enumeradas devuelven el nombre o el número crudo.
+
+type Table = &'static [(u64, &'static str)];
+
+macro_rules! table {
+    ($($c:ident),* $(,)?) => {
+        &[$((libc::$c as u32 as u64, stringify!($c))),*]
+    };
+}
+
+/// Combina los bits conocidos de `value` con '|'; los restantes van en hex al final.
+/// Sin ningún bit se imprime `0`, como strace.
+fn fmt_bits(value: u64, table: Table) -> String {
+    if value == 0 {
+        return "0".to_string();
+    }
+    let mut parts: Vec<String> = Vec::new();
+    let mut rest = value;
+    for &(bit, name) in table {
+        if bit != 0 && value & bit == bit && rest & bit != 0 {
+            parts.push(name.to_string());
+            rest &= !bit;
+        }
+    }
+    if rest != 0 || parts.is_empty() {
+        parts.push(format!("0x{:x}", rest));
+    }
+    parts.join("|")
+}
+
+fn fmt_enum(value: u64, table: Table) -> String {
+    match table.iter().find(|&&(v, _)| v == value) {
+        Some(&(_, name)) => name.to_string(),
+        None => format!("{}", value as u32 as i32),
+    }
+}
+
+// O_SYNC incluye el bit de O_DSYNC y O_TMPFILE el de O_DIRECTORY: cada máscara compuesta
+// va antes que su componente para que fmt_bits la tome entera y no imprima ambas
+const OPEN: Table = table![
+    O_CREAT, O_EXCL, O_NOCTTY, O_TRUNC, O_APPEND, O_NONBLOCK, O_SYNC, O_DSYNC, O_DIRECT, O_LARGEFILE,
+    O_TMPFILE, O_DIRECTORY, O_NOFOLLOW, O_NOATIME, O_CLOEXEC, O_PATH, O_ASYNC,
+];
+
+const AT: Table = table![
+    AT_SYMLINK_NOFOLLOW, AT_REMOVEDIR, AT_SYMLINK_FOLLOW, AT_NO_AUTOMOUNT, AT_EMPTY_PATH, AT_EACCESS,
+    AT_STATX_FORCE_SYNC, AT_STATX_DONT_SYNC, AT_RECURSIVE,
+];
+
+const ACCESS: Table = table![R_OK, W_OK, X_OK];
+
+const MMAP_PROT: Table = table![PROT_READ, PROT_WRITE, PROT_EXEC, PROT_GROWSDOWN, PROT_GROWSUP];
+
+const MMAP_FLAGS: Table = table![
+    MAP_SHARED, MAP_PRIVATE, MAP_FIXED, MAP_ANONYMOUS, MAP_GROWSDOWN, MAP_DENYWRITE, MAP_EXECUTABLE,
+    MAP_LOCKED, MAP_NORESERVE, MAP_POPULATE, MAP_NONBLOCK, MAP_STACK, MAP_HUGETLB, MAP_SYNC,
+    MAP_FIXED_NOREPLACE,
+];
+
+const MADVISE: Table = table![
+    MADV_NORMAL, MADV_RANDOM, MADV_SEQUENTIAL, MADV_WILLNEED, MADV_DONTNEED, MADV_FREE, MADV_REMOVE,
+    MADV_DONTFORK, MADV_DOFORK, MADV_MERGEABLE, MADV_UNMERGEABLE, MADV_HUGEPAGE, MADV_NOHUGEPAGE,
+    MADV_DONTDUMP, MADV_DODUMP, MADV_WIPEONFORK, MADV_KEEPONFORK, MADV_COLD, MADV_PAGEOUT, MADV_HWPOISON,
+];
+
+const MREMAP: Table = table![MREMAP_MAYMOVE, MREMAP_FIXED];
+
+const MSYNC: Table = table![MS_ASYNC, MS_INVALIDATE, MS_SYNC];
+
+const WHENCE: Table = table![SEEK_SET, SEEK_CUR, SEEK_END, SEEK_DATA, SEEK_HOLE];
+
+// El byte bajo de los flags de clone es la señal de terminación (se decodifica aparte)
+const CLONE: Table = table![
+    CLONE_VM, CLONE_FS, CLONE_FILES, CLONE_SIGHAND, CLONE_PIDFD, CLONE_PTRACE, CLONE_VFORK,
+    CLONE_PARENT, CLONE_THREAD, CLONE_NEWNS, CLONE_SYSVSEM, CLONE_SETTLS, CLONE_PARENT_SETTID,
+    CLONE_CHILD_CLEARTID, CLONE_DETACHED, CLONE_UNTRACED, CLONE_CHILD_SETTID, CLONE_NEWCGROUP,
+    CLONE_NEWUTS, CLONE_NEWIPC, CLONE_NEWUSER, CLONE_NEWPID, CLONE_NEWNET, CLONE_IO,
+];
+
+const SOCK_DOMAIN: Table = table![AF_UNSPEC, AF_UNIX, AF_INET, AF_INET6, AF_NETLINK, AF_PACKET, AF_VSOCK];
+
+const SOCK_TYPE: Table = table![SOCK_STREAM, SOCK_DGRAM, SOCK_RAW, SOCK_RDM, SOCK_SEQPACKET];
+
+const SOCK_TYPE_FLAGS: Table = table![SOCK_NONBLOCK, SOCK_CLOEXEC];
+
+const MSG: Table = table![
+    MSG_OOB, MSG_PEEK, MSG_DONTROUTE, MSG_CTRUNC, MSG_TRUNC, MSG_DONTWAIT, MSG_EOR, MSG_WAITALL,
+    MSG_NOSIGNAL, MSG_MORE, MSG_WAITFORONE, MSG_CMSG_CLOEXEC,
+];
+
+const SHUTDOWN: Table = table![SHUT_RD, SHUT_WR, SHUT_RDWR];
+
+const FCNTL: Table = table![
+    F_DUPFD, F_GETFD, F_SETFD, F_GETFL, F_SETFL, F_GETLK, F_SETLK, F_SETLKW, F_SETOWN, F_GETOWN,
+    F_OFD_GETLK, F_OFD_SETLK, F_OFD_SETLKW, F_SETLEASE, F_GETLEASE, F_NOTIFY,
+    F_DUPFD_CLOEXEC, F_SETPIPE_SZ, F_GETPIPE_SZ, F_ADD_SEALS, F_GET_SEALS,
+];
+
+const FLOCK: Table = table![LOCK_SH, LOCK_EX, LOCK_NB, LOCK_UN];
+
+const FUTEX_OPS: Table = table![
+    FUTEX_WAIT, FUTEX_WAKE, FUTEX_FD, FUTEX_REQUEUE, FUTEX_CMP_REQUEUE, FUTEX_WAKE_OP, FUTEX_LOCK_PI,
+    FUTEX_UNLOCK_PI, FUTEX_TRYLOCK_PI, FUTEX_WAIT_BITSET, FUTEX_WAKE_BITSET, FUTEX_WAIT_REQUEUE_PI,
+    FUTEX_CMP_REQUEUE_PI, FUTEX_LOCK_PI2,
+];
+
+const SIG_HOW: Table = table![SIG_BLOCK, SIG_UNBLOCK, SIG_SETMASK];
+
+const WAIT: Table = table![WNOHANG, WUNTRACED, WEXITED, WCONTINUED, WNOWAIT, __WALL, __WCLONE];
+
+const EPOLL_CTL: Table = table![EPOLL_CTL_ADD, EPOLL_CTL_DEL, EPOLL_CTL_MOD];
+
+const RLIMIT: Table = table![
+    RLIMIT_CPU, RLIMIT_FSIZE, RLIMIT_DATA, RLIMIT_STACK, RLIMIT_CORE, RLIMIT_RSS, RLIMIT_NPROC,
+    RLIMIT_NOFILE, RLIMIT_MEMLOCK, RLIMIT_AS, RLIMIT_LOCKS, RLIMIT_SIGPENDING, RLIMIT_MSGQUEUE,
+    RLIMIT_NICE, RLIMIT_RTPRIO, RLIMIT_RTTIME,
+];
+
+// asm/prctl.h (no todos están en libc)
+const ARCH_PRCTL: Table = &[
+    (0x1001, "ARCH_SET_GS"),
+    (0x1002, "ARCH_SET_FS"),
+    (0x1003, "ARCH_GET_FS"),
+    (0x1004, "ARCH_GET_GS"),
+    (0x1011, "ARCH_GET_CPUID"),
+    (0x1012, "ARCH_SET_CPUID"),
+    (0x1021, "ARCH_GET_XCOMP_SUPP"),
+    (0x1022, "ARCH_GET_XCOMP_PERM"),
+    (0x1023, "ARCH_REQ_XCOMP_PERM"),
+    (0x2001, "ARCH_MAP_VDSO_X32"),
+    (0x2002, "ARCH_MAP_VDSO_32"),
+    (0x2003, "ARCH_MAP_VDSO_64"),
+    (0x3001, "ARCH_CET_STATUS"),
+    (0x4001, "ARCH_GET_UNTAG_MASK"),
+    (0x4002, "ARCH_ENABLE_TAGGED_ADDR"),
+    (0x5001, "ARCH_SHSTK_ENABLE"),
+    (0x5002, "ARCH_SHSTK_DISABLE"),
+];
+
+const PRCTL: Table = table![
+    PR_SET_PDEATHSIG, PR_GET_PDEATHSIG, PR_GET_DUMPABLE, PR_SET_DUMPABLE, PR_GET_KEEPCAPS,
+    PR_SET_KEEPCAPS, PR_SET_NAME, PR_GET_NAME, PR_GET_SECCOMP, PR_SET_SECCOMP, PR_CAPBSET_READ,
+    PR_CAPBSET_DROP, PR_GET_TIMERSLACK, PR_SET_TIMERSLACK, PR_SET_MM, PR_SET_CHILD_SUBREAPER,
+    PR_GET_CHILD_SUBREAPER, PR_SET_NO_NEW_PRIVS, PR_GET_NO_NEW_PRIVS, PR_GET_TID_ADDRESS,
+    PR_SET_THP_DISABLE, PR_GET_THP_DISABLE, PR_CAP_AMBIENT, PR_SET_VMA,
+];
+
+const IOCTL: Table = table![
+    TCGETS, TCSETS, TCSETSW, TCSETSF, TIOCGWINSZ, TIOCSWINSZ, TIOCGPGRP, TIOCSPGRP, TIOCSCTTY,
+    TIOCNOTTY, TIOCOUTQ, TIOCSTI, TIOCGPTN, TIOCSPTLCK, FIONREAD, FIONBIO, FIOCLEX, FIONCLEX,
+    FIOASYNC,
+];
+
+const GETRANDOM: Table = table![GRND_NONBLOCK, GRND_RANDOM, GRND_INSECURE];
+
+const RENAME: Table = table![RENAME_NOREPLACE, RENAME_EXCHANGE, RENAME_WHITEOUT];
+
+// linux/close_range.h
+const CLOSE_RANGE: Table = &[(1 << 1, "CLOSE_RANGE_UNSHARE"), (1 << 2, "CLOSE_RANGE_CLOEXEC")];
+
+/// Renderiza `value` según la familia de flags declarada en el esquema.
+/// Todos los flags son int/unsigned en el kernel: solo cuentan los 32 bits bajos.
+pub fn fmt_flags(kind: FlagKind, value: u64) -> String {
+    let value = value as u32 as u64;
+    match kind {
+        FlagKind::Open => fmt_open(value),
+        FlagKind::At => fmt_at(value),
+        FlagKind::Access if value == 0 => "F_OK".to_string(),
+        FlagKind::Access => fmt_bits(value, ACCESS),
+        FlagKind::MmapProt if value == 0 => "PROT_NONE".to_string(),
+        FlagKind::MmapProt => fmt_bits(value, MMAP_PROT),
+        FlagKind::MmapFlags => fmt_bits(value, MMAP_FLAGS),
+        FlagKind::Madvise => fmt_enum(value, MADVISE),
+        FlagKind::Mremap => fmt_bits(value, MREMAP),
+        FlagKind::Msync => fmt_bits(value, MSYNC),
+        FlagKind::Whence => fmt_enum(value, WHENCE),
+        FlagKind::Clone => fmt_clone(value),
+        FlagKind::SockDomain => fmt_enum(value, SOCK_DOMAIN),
+        FlagKind::SockType => fmt_sock_type(value),
+        FlagKind::Msg => fmt_bits(value, MSG),
+        FlagKind::Shutdown => fmt_enum(value, SHUTDOWN),
+        FlagKind::Fcntl => fmt_enum(value, FCNTL),
+        FlagKind::Flock => fmt_bits(value, FLOCK),
+        FlagKind::Futex => fmt_futex(value),
+        FlagKind::SigHow => fmt_enum(value, SIG_HOW),
+        FlagKind::Wait => fmt_bits(value, WAIT),
+        FlagKind::EpollCtl => fmt_enum(value, EPOLL_CTL),
+        FlagKind::Rlimit => fmt_enum(value, RLIMIT),
+        FlagKind::ArchPrctl => fmt_enum(value, ARCH_PRCTL),
+        FlagKind::Prctl => fmt_enum(value, PRCTL),
+        FlagKind::Ioctl => fmt_ioctl(value),
+        FlagKind::Getrandom => fmt_bits(value, GETRANDOM),
+        FlagKind::Rename => fmt_bits(value, RENAME),
+        FlagKind::CloseRange => fmt_bits(value, CLOSE_RANGE),
+    }
+}
+
+/// O_* con el modo de acceso primero (O_RDONLY vale 0 y no se detecta como bit).
+pub fn fmt_open(value: u64) -> String {
+    let flags = value as i32;
+    let acc = match flags & libc::O_ACCMODE {
+        libc::O_RDONLY => "O_RDONLY",
+        libc::O_WRONLY => "O_WRONLY",
+        libc::O_RDWR => "O_RDWR",
+        _ => "O_ACCMODE",
+    };
+    let rest = (flags & !libc::O_ACCMODE) as u32 as u64;
+    if rest == 0 {
+        return acc.to_string();
+    }
+    format!("{}|{}", acc, fmt_bits(rest, OPEN))
+}
+
+fn fmt_at(value: u64) -> String {
+    fmt_bits(value, AT)
+}
+
+fn fmt_clone(value: u64) -> String {
+    let exit_signal = value & 0xff;
+    let bits = value & !0xff;
+    let mut out = if bits != 0 { fmt_bits(bits, CLONE) } else { String::new() };
+    if exit_signal != 0 {
+        if !out.is_empty() {
+            out.push('|');
+        }
+        out.push_str(&super::signal_name(exit_signal as i32));
+    }
+    if out.is_empty() {
+        out.push('0');
+    }
+    out
+}
+
+fn fmt_sock_type(value: u64) -> String {
+    let extra = value & (libc::SOCK_NONBLOCK | libc::SOCK_CLOEXEC) as u64;
+    let base = fmt_enum(value & !extra, SOCK_TYPE);
+    if extra == 0 {
+        base
+    } else {
+        format!("{}|{}", base, fmt_bits(extra, SOCK_TYPE_FLAGS))
+    }
+}
+
+fn fmt_futex(value: u64) -> String {
+    let op = value & !((libc::FUTEX_PRIVATE_FLAG | libc::FUTEX_CLOCK_REALTIME) as u64);
+    let mut out = fmt_enum(op, FUTEX_OPS);
+    if value & libc::FUTEX_PRIVATE_FLAG as u64 != 0 {
+        out.push_str("_PRIVATE");
+    }
+    if value & libc::FUTEX_CLOCK_REALTIME as u64 != 0 {
+        out.push_str("|FUTEX_CLOCK_REALTIME");
+    }
+    out
+}
+
+fn fmt_ioctl(value: u64) -> String {
+    match IOCTL.iter().find(|&&(v, _)| v == value) {
+        Some(&(_, name)) => name.to_string(),
+        None => format!("0x{:x}", value),
+    }
+}
+
+const CLOCKS: Table = table![
+    CLOCK_REALTIME, CLOCK_MONOTONIC, CLOCK_PROCESS_CPUTIME_ID, CLOCK_THREAD_CPUTIME_ID,
+    CLOCK_MONOTONIC_RAW, CLOCK_REALTIME_COARSE, CLOCK_MONOTONIC_COARSE, CLOCK_BOOTTIME,
+    CLOCK_REALTIME_ALARM, CLOCK_BOOTTIME_ALARM, CLOCK_TAI,
+];
+
+pub fn fmt_clock(value: u64) -> String {
+    fmt_enum(value as u32 as u64, CLOCKS)
+}
+
+// asm/signal.h: SA_RESTORER no está en libc
+const SA_FLAGS: Table = &[
+    (libc::SA_NOCLDSTOP as u64, "SA_NOCLDSTOP"),
+    (libc::SA_NOCLDWAIT as u64, "SA_NOCLDWAIT"),
+    (libc::SA_SIGINFO as u64, "SA_SIGINFO"),
+    (0x0400_0000, "SA_RESTORER"),
+    (libc::SA_ONSTACK as u64, "SA_ONSTACK"),
+    (libc::SA_RESTART as u64, "SA_RESTART"),
+    (libc::SA_NODEFER as u64, "SA_NODEFER"),
+    (libc::SA_RESETHAND as u64, "SA_RESETHAND"),
+];
+
+pub fn fmt_sa_flags(value: u64) -> String {
+    fmt_bits(value, SA_FLAGS)
+}
+
+const POLL_EVENTS: Table = table![
+    POLLIN, POLLPRI, POLLOUT, POLLERR, POLLHUP, POLLNVAL, POLLRDNORM, POLLRDBAND, POLLWRNORM,
+    POLLWRBAND, POLLRDHUP,
+];
+
+pub fn fmt_poll_events(value: u64) -> String {
+    fmt_bits(value, POLL_EVENTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_y_ceros() {
+        assert_eq!(fmt_flags(FlagKind::Wait, 0), "0");
+        assert_eq!(fmt_flags(FlagKind::Wait, libc::WNOHANG as u64), "WNOHANG");
+        assert_eq!(fmt_flags(FlagKind::Access, 0), "F_OK");
+        assert_eq!(fmt_flags(FlagKind::MmapProt, 0), "PROT_NONE");
+        assert_eq!(fmt_flags(FlagKind::MmapProt, 0x3), "PROT_READ|PROT_WRITE");
+        // Lo desconocido queda en hex al final
+        assert_eq!(fmt_flags(FlagKind::Access, 0x4 | 0x40), "R_OK|0x40");
+        assert_eq!(fmt_open((libc::O_WRONLY | libc::O_CREAT | libc::O_CLOEXEC) as u64), "O_WRONLY|O_CREAT|O_CLOEXEC");
+        assert_eq!(fmt_open(0), "O_RDONLY");
+        // Máscaras compuestas
+        assert_eq!(fmt_open((libc::O_WRONLY | libc::O_SYNC) as u64), "O_WRONLY|O_SYNC");
+        assert_eq!(fmt_open((libc::O_WRONLY | libc::O_DSYNC) as u64), "O_WRONLY|O_DSYNC");
+        assert_eq!(fmt_open((libc::O_RDWR | libc::O_TMPFILE) as u64), "O_RDWR|O_TMPFILE");
+        assert_eq!(fmt_open((libc::O_RDONLY | libc::O_DIRECTORY) as u64), "O_RDONLY|O_DIRECTORY");
+    }
+}
@@ -0,0 +1,321 @@
+use crate::arch::Abi;
+use nix::unistd::Pid;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use super::flags::{fmt_clock, fmt_flags, fmt_poll_events};
+use super::schema::{syscall_spec, ArgType, RetType, StructKind};
+use super::structs::{abi_independent, fmt_struct, read_words};
+use super::{decode_errno, escape_bytes, read_bytes, read_c_string, read_ptr, signal_name};
+
+// === Formateador genérico ===
+// Recorre el esquema de la syscall y renderiza cada argumento según su tipo. Las
+// structs de entrada solo se leen para la ABI nativa (en i386 cambian los tamaños);
+// los punteros a datos que llena el kernel se muestran como dirección.
+
+const BUF_PREVIEW: usize = 32; // bytes mostrados de un buffer
+const MAX_ITEMS: usize = 8; // elementos mostrados de argv, iovecs, pollfds…
+const MAX_SOCKADDR: usize = 128; // sizeof(struct sockaddr_storage)
+
+/// Decodifica los argumentos reales de la syscall como pares (nombre, valor) según el
+/// esquema; None si la syscall no tiene esquema (el llamador muestra los valores crudos).
+pub fn decode_args(pid: Pid, abi: Abi, name: &str, args: &[u64; 6]) -> Option<Vec<(&'static str, String)>> {
+    let spec = syscall_spec(abi, name)?;
+    Some(
+        spec.args
+            .iter()
+            .enumerate()
+            .map(|(i, &(arg_name, ty))| (arg_name, format_arg(pid, abi, ty, args, i)))
+            .collect(),
+    )
+}
+
+/// Nombre del errno si el retorno crudo está en el rango de error [-4095, -1].
+pub fn error_name(ret: i64) -> Option<String> {
+    (-4095..0).contains(&ret).then(|| decode_errno(-ret as i32))
+}
+
+/// Renderiza el retorno: `-1 ERRNO` en error, y si no según el RetType del esquema.
+pub fn format_return(abi: Abi, name: &str, ret: i64) -> String {
+    if let Some(errno) = error_name(ret) {
+        return format!("-1 {}", errno);
+    }
+    match syscall_spec(abi, name).map(|s| s.ret) {
+        Some(RetType::Hex) => format!("0x{:x}", ret),
+        Some(RetType::Mode) => fmt_mode(ret as u32),
+        _ => format!("{}", ret),
+    }
+}
+
+/// Índice del primer parámetro que llena el kernel: strace imprime en la entrada solo
+/// los argumentos anteriores y el resto en la salida.
+pub fn first_out_arg(abi: Abi, name: &str) -> Option<usize> {
+    syscall_spec(abi, name)?.args.iter().position(|&(_, ty)| {
+        matches!(ty, ArgType::Out(_) | ArgType::BufOut(_) | ArgType::IovecOut(_) | ArgType::SockaddrOut(_))
+    })
+}
+
+/// Renderiza a la salida los parámetros que llena el kernel (buffers, structs,
+/// sockaddr e iovecs de lectura) como pares (nombre, valor). Solo si la syscall tuvo
+/// éxito: en error el contenido no está definido. `args` son los de la entrada.
+pub fn decode_out_args(pid: Pid, abi: Abi, name: &str, args: &[u64; 6], ret: i64) -> Vec<(&'static str, String)> {
+    let Some(spec) = syscall_spec(abi, name) else {
+        return Vec::new();
+    };
+    if ret < 0 {
+        return Vec::new();
+    }
+    spec.args
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(arg_name, ty))| {
+            let v = args[i];
+            if v == 0 {
+                return None; // puntero opcional no provisto
+            }
+            let value = match ty {
+                // El retorno es la cantidad de bytes escritos en el buffer
+                ArgType::BufOut(_) => fmt_buffer(pid, v, ret as usize),
+                ArgType::IovecOut(cnt_idx) => fmt_iovec_out(pid, abi, v, args[cnt_idx] as u32 as usize, ret as usize),
+                ArgType::SockaddrOut(len_idx) => fmt_sockaddr_out(pid, v, args[len_idx])?,
+                ArgType::Out(kind) => fmt_out_struct(pid, abi, kind, v, ret)?,
+                _ => return None,
+            };
+            Some((arg_name, value))
+        })
+        .collect()
+}
+
+fn format_arg(pid: Pid, abi: Abi, ty: ArgType, args: &[u64; 6], i: usize) -> String {
+    let v = args[i];
+    match ty {
+        ArgType::Int | ArgType::Fd | ArgType::Pid => format!("{}", v as i32),
+        ArgType::Uint => format!("{}", v as u32),
+        ArgType::Hex => format!("0x{:x}", v),
+        ArgType::Ptr | ArgType::Out(_) | ArgType::BufOut(_) | ArgType::IovecOut(_) | ArgType::SockaddrOut(_) => {
+            fmt_ptr(v)
+        }
+        ArgType::DirFd if v as i32 == libc::AT_FDCWD => "AT_FDCWD".to_string(),
+        ArgType::DirFd => format!("{}", v as i32),
+        ArgType::Path | ArgType::Str => fmt_string(pid, v),
+        ArgType::Size => format!("{}", ulong(abi, v)),
+        ArgType::Off => format!("{}", slong(abi, v)),
+        ArgType::Mode => fmt_mode(v as u32),
+        ArgType::Uid => format!("{}", v as u32 as i32),
+        ArgType::Signal => signal_name(v as i32),
+        ArgType::Clock => fmt_clock(v),
+        ArgType::Flags(kind) => fmt_flags(kind, v),
+        ArgType::In(kind) => fmt_in_struct(pid, abi, kind, v),
+        ArgType::BufIn(len_idx) => fmt_buffer(pid, v, ulong(abi, args[len_idx]) as usize),
+        ArgType::Sockaddr(len_idx) => fmt_sockaddr(pid, v, args[len_idx] as u32 as usize),
+        ArgType::IovecIn(cnt_idx) => fmt_iovec_in(pid, abi, v, args[cnt_idx] as u32 as usize),
+        ArgType::Pollfds(cnt_idx) => fmt_pollfds(pid, v, args[cnt_idx] as u32 as usize),
+        ArgType::Argv => fmt_argv(pid, abi, v),
+    }
+}
+
+// long/unsigned long miden 32 bits en i386
+fn ulong(abi: Abi, v: u64) -> u64 {
+    if abi.pointer_size() == 4 { v as u32 as u64 } else { v }
+}
+
+fn slong(abi: Abi, v: u64) -> i64 {
+    if abi.pointer_size() == 4 { v as u32 as i32 as i64 } else { v as i64 }
+}
+
+fn fmt_mode(mode: u32) -> String {
+    if mode == 0 { "0".to_string() } else { format!("0{:o}", mode) }
+}
+
+fn fmt_ptr(addr: u64) -> String {
+    if addr == 0 { "NULL".to_string() } else { format!("0x{:x}", addr) }
+}
+
+fn fmt_string(pid: Pid, addr: u64) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    match read_c_string(pid, addr) {
+        Ok(s) => escape_bytes(&s),
+        Err(_) => format!("0x{:x}", addr),
+    }
+}
+
+/// Primeros BUF_PREVIEW bytes de un buffer, con "..." si hay más.
+fn fmt_buffer(pid: Pid, addr: u64, len: usize) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    let n = len.min(BUF_PREVIEW);
+    match read_bytes(pid, addr, n) {
+        Ok(bytes) if len > n => format!("{}...", escape_bytes(&bytes)),
+        Ok(bytes) => escape_bytes(&bytes),
+        Err(_) => format!("0x{:x}", addr),
+    }
+}
+
+fn fmt_in_struct(pid: Pid, abi: Abi, kind: StructKind, addr: u64) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    if abi != Abi::native() && !abi_independent(kind) {
+        return format!("0x{:x}", addr);
+    }
+    fmt_struct(pid, kind, addr, 0).unwrap_or_else(|| format!("0x{:x}", addr))
+}
+
+fn fmt_out_struct(pid: Pid, abi: Abi, kind: StructKind, addr: u64, ret: i64) -> Option<String> {
+    if abi != Abi::native() && !abi_independent(kind) {
+        return None;
+    }
+    fmt_struct(pid, kind, addr, ret)
+}
+
+fn fmt_sockaddr(pid: Pid, addr: u64, len: usize) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    match read_bytes(pid, addr, len.min(MAX_SOCKADDR)) {
+        Ok(bytes) => fmt_sockaddr_bytes(&bytes),
+        Err(_) => format!("0x{:x}", addr),
+    }
+}
+
+/// sockaddr de salida: su longitud real la deja el kernel en *addrlen.
+fn fmt_sockaddr_out(pid: Pid, addr: u64, addrlen_ptr: u64) -> Option<String> {
+    if addrlen_ptr == 0 {
+        return None;
+    }
+    let len = read_bytes(pid, addrlen_ptr, 4).ok()?;
+    let len = u32::from_ne_bytes(len.try_into().unwrap()) as usize;
+    Some(fmt_sockaddr(pid, addr, len))
+}
+
+/// Renderiza una sockaddr ya leída (AF_UNIX, AF_INET, AF_INET6).
+fn fmt_sockaddr_bytes(b: &[u8]) -> String {
+    if b.len() < 2 {
+        return "{}".to_string();
+    }
+    let family = u16::from_ne_bytes([b[0], b[1]]) as i32;
+    match family {
+        libc::AF_UNIX => {
+            let path = &b[2..];
+            // Abstractos: primer byte NUL, el nombre ocupa el resto
+            if path.first() == Some(&0) && path.len() > 1 {
+                return format!("{{sa_family=AF_UNIX, sun_path=@{}}}", escape_bytes(&path[1..]));
+            }
+            let end = path.iter().position(|&c| c == 0).unwrap_or(path.len());
+            format!("{{sa_family=AF_UNIX, sun_path={}}}", escape_bytes(&path[..end]))
+        }
+        libc::AF_INET if b.len() >= 8 => {
+            let port = u16::from_be_bytes([b[2], b[3]]);
+            let ip = Ipv4Addr::new(b[4], b[5], b[6], b[7]);
+            format!("{{sa_family=AF_INET, sin_port={}, sin_addr={}}}", port, ip)
+        }
+        libc::AF_INET6 if b.len() >= 24 => {
+            let port = u16::from_be_bytes([b[2], b[3]]);
+            let octets: [u8; 16] = b[8..24].try_into().unwrap();
+            format!("{{sa_family=AF_INET6, sin6_port={}, sin6_addr={}}}", port, Ipv6Addr::from(octets))
+        }
+        _ => format!("{{sa_family={}}}", family),
+    }
+}
+
+fn fmt_iovec_in(pid: Pid, abi: Abi, addr: u64, count: usize) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    if abi != Abi::native() {
+        return format!("0x{:x}", addr);
+    }
+    let n = count.min(MAX_ITEMS);
+    let Ok(w) = read_words(pid, addr, n * 2) else {
+        return format!("0x{:x}", addr);
+    };
+    let mut items: Vec<String> = w
+        .chunks_exact(2)
+        .map(|iov| format!("{{iov_base={}, iov_len={}}}", fmt_buffer(pid, iov[0], iov[1] as usize), iov[1]))
+        .collect();
+    if count > n {
+        items.push("...".to_string());
+    }
+    format!("[{}]", items.join(", "))
+}
+
+/// iovecs de lectura a la salida: el kernel llenó `total` bytes en orden.
+fn fmt_iovec_out(pid: Pid, abi: Abi, addr: u64, count: usize, total: usize) -> String {
+    if abi != Abi::native() {
+        return format!("0x{:x}", addr);
+    }
+    let n = count.min(MAX_ITEMS);
+    let Ok(w) = read_words(pid, addr, n * 2) else {
+        return format!("0x{:x}", addr);
+    };
+    let mut left = total;
+    let mut items: Vec<String> = w
+        .chunks_exact(2)
+        .map(|iov| {
+            let filled = left.min(iov[1] as usize);
+            left -= filled;
+            format!("{{iov_base={}, iov_len={}}}", fmt_buffer(pid, iov[0], filled), iov[1])
+        })
+        .collect();
+    if count > n {
+        items.push("...".to_string());
+    }
+    format!("[{}]", items.join(", "))
+}
+
+fn fmt_pollfds(pid: Pid, addr: u64, count: usize) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    // struct pollfd { int fd; short events; short revents; } = 8 bytes en todas las ABIs
+    let n = count.min(MAX_ITEMS);
+    let Ok(bytes) = read_bytes(pid, addr, n * 8) else {
+        return format!("0x{:x}", addr);
+    };
+    let mut items: Vec<String> = bytes
+        .chunks_exact(8)
+        .map(|p| {
+            let fd = i32::from_ne_bytes([p[0], p[1], p[2], p[3]]);
+            let events = u16::from_ne_bytes([p[4], p[5]]) as u64;
+            format!("{{fd={}, events={}}}", fd, fmt_poll_events(events))
+        })
+        .collect();
+    if count > n {
+        items.push("...".to_string());
+    }
+    format!("[{}]", items.join(", "))
+}
+
+fn fmt_argv(pid: Pid, abi: Abi, addr: u64) -> String {
+    if addr == 0 {
+        return "NULL".to_string();
+    }
+    let (mut items, truncated) = read_argv(pid, addr, MAX_ITEMS, abi);
+    if truncated {
+        items.push("...".to_string());
+    }
+    format!("[{}]", items.join(", "))
+}
+
+/// Lee y escapa hasta `max_items` cadenas de un char** terminado en NULL (punteros de
+/// 4 bytes en tracees i386: se lee una palabra y se toma la mitad baja). El bool indica
+/// si quedaron elementos sin leer: tras `max_items` se mira la entrada siguiente y solo
+/// cuenta si no es el NULL final.
+fn read_argv(pid: Pid, argv_ptr: u64, max_items: usize, abi: Abi) -> (Vec<String>, bool) {
+    let mut out = Vec::new();
+    let word_size = abi.pointer_size();
+    let mut p = argv_ptr;
+    loop {
+        let ptr = read_ptr(pid, p).map(|w| if word_size == 4 { w as u32 as usize } else { w });
+        match ptr {
+            Ok(0) | Err(_) => return (out, false), // NULL termina
+            Ok(_) if out.len() == max_items => return (out, true),
+            Ok(s_ptr) => {
+                out.push(read_c_string(pid, s_ptr as u64).map_or_else(|_| format!("0x{:x}", s_ptr), |s| escape_bytes(&s)));
+                p += word_size;
+            }
+        }
+    }
+}
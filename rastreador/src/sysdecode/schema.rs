@@ -0,0 +1,729 @@
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+use crate::arch::Abi;
+
+// === Esquema declarativo de argumentos ===
+// Cada syscall conocida declara cuántos argumentos tiene, cómo se llaman y de qué
+// tipo son; el formateador genérico de sysdecode recorre esta tabla para imprimir
+// solo los argumentos reales con una representación adecuada.
+
+/// Tipo de un argumento de syscall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgType {
+    Int,               // entero con signo (decimal)
+    Uint,              // entero sin signo (decimal)
+    Hex,               // valor opaco en hexadecimal
+    Ptr,               // dirección sin decodificar (NULL si es 0)
+    Fd,                // descriptor de archivo
+    DirFd,             // descriptor de directorio de las *at (AT_FDCWD)
+    Path,              // char* con una ruta
+    Str,               // char* con una cadena que no es ruta (nombres, tipos de fs…)
+    Size,              // size_t
+    Off,               // off_t / loff_t
+    Mode,              // mode_t (octal)
+    Pid,               // pid/tid
+    Uid,               // uid/gid (-1 = sin cambio)
+    Signal,            // número de señal
+    Clock,             // clockid_t
+    Flags(FlagKind),   // flags o valores enumerados de un tipo concreto
+    In(StructKind),    // puntero a struct que lee el kernel (se decodifica en la entrada)
+    Out(StructKind),   // puntero a struct que llena el kernel (válido solo a la salida)
+    BufIn(usize),      // buffer de entrada; su longitud está en el argumento indicado
+    BufOut(usize),     // buffer que llena el kernel; longitud máxima en el argumento indicado
+    Sockaddr(usize),   // struct sockaddr*; su longitud está en el argumento indicado
+    SockaddrOut(usize), // struct sockaddr* que llena el kernel; socklen_t* en el argumento indicado
+    IovecIn(usize),    // struct iovec[] con datos de entrada; la cantidad está en el argumento indicado
+    IovecOut(usize),   // struct iovec[] que llena el kernel; la cantidad está en el argumento indicado
+    Pollfds(usize),    // struct pollfd[]; la cantidad está en el argumento indicado
+    Argv,              // char *const argv[]
+}
+
+/// Familias de flags/enumerados que sabe decodificar sysdecode::flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagKind {
+    Open,      // O_*
+    At,        // AT_*
+    Access,    // F_OK/R_OK/W_OK/X_OK
+    MmapProt,  // PROT_*
+    MmapFlags, // MAP_*
+    Madvise,   // MADV_*
+    Mremap,    // MREMAP_*
+    Msync,     // MS_*
+    Whence,    // SEEK_*
+    Clone,     // CLONE_*
+    SockDomain,
+    SockType,  // SOCK_* | SOCK_NONBLOCK | SOCK_CLOEXEC
+    Msg,       // MSG_*
+    Shutdown,  // SHUT_*
+    Fcntl,     // F_*
+    Flock,     // LOCK_*
+    Futex,     // FUTEX_*
+    SigHow,    // SIG_BLOCK/SIG_UNBLOCK/SIG_SETMASK
+    Wait,      // WNOHANG, WUNTRACED…
+    EpollCtl,  // EPOLL_CTL_*
+    Rlimit,    // RLIMIT_*
+    ArchPrctl, // ARCH_*
+    Prctl,     // PR_*
+    Ioctl,     // peticiones ioctl más comunes
+    Getrandom, // GRND_*
+    Rename,    // RENAME_*
+    CloseRange,
+}
+
+/// Structs que sabe leer sysdecode desde la memoria del tracee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StructKind {
+    Timespec,
+    Timeval,
+    Itimerval,
+    Sigset,
+    Sigaction,
+    Rlimit,
+    FdPair,   // int[2] de pipe/socketpair
+    Stat,
+    Statx,
+    Statfs,
+    Utsname,
+    Sysinfo,
+    Rusage,
+    Tms,
+    WaitStatus,
+    Dirents64,
+    CStr,     // buffer de salida con una cadena terminada en NUL (getcwd)
+}
+
+/// Cómo se interpreta el valor de retorno en caso de éxito.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetType {
+    Int,  // entero / conteo de bytes
+    Fd,   // descriptor nuevo
+    Hex,  // dirección (mmap, brk…)
+    Pid,
+    Mode, // umask
+}
+
+pub struct SyscallSpec {
+    pub name: &'static str,
+    pub args: &'static [(&'static str, ArgType)],
+    pub ret: RetType,
+}
+
+const fn sc(name: &'static str, args: &'static [(&'static str, ArgType)], ret: RetType) -> SyscallSpec {
+    SyscallSpec { name, args, ret }
+}
+
+/// Busca el esquema de una syscall en la ABI dada: primero las excepciones de esa ABI
+/// (OVERRIDES) y si no la entrada común por nombre.
+pub fn syscall_spec(abi: Abi, name: &str) -> Option<&'static SyscallSpec> {
+    static BY_NAME: Lazy<HashMap<&'static str, &'static SyscallSpec>> =
+        Lazy::new(|| SPECS.iter().map(|s| (s.name, s)).collect());
+    OVERRIDES
+        .iter()
+        .find(|(a, s)| *a == abi && s.name == name)
+        .map(|(_, s)| s)
+        .or_else(|| BY_NAME.get(name).copied())
+}
+
+use ArgType::*;
+use FlagKind as F;
+use RetType as R;
+use StructKind as S;
+
+// Argumentos repetidos en muchas entradas
+const STAT_PATH: &[(&str, ArgType)] = &[("pathname", Path), ("statbuf", Out(S::Stat))];
+const XATTR_SET: &[(&str, ArgType)] = &[("pathname", Path), ("name", Str), ("value", BufIn(3)), ("size", Size), ("flags", Hex)];
+const XATTR_GET: &[(&str, ArgType)] = &[("pathname", Path), ("name", Str), ("value", BufOut(3)), ("size", Size)];
+const XATTR_LIST: &[(&str, ArgType)] = &[("pathname", Path), ("list", Ptr), ("size", Size)];
+const XATTR_REMOVE: &[(&str, ArgType)] = &[("pathname", Path), ("name", Str)];
+const MMAP: &[(&str, ArgType)] = &[
+    ("addr", Ptr),
+    ("length", Size),
+    ("prot", Flags(F::MmapProt)),
+    ("flags", Flags(F::MmapFlags)),
+    ("fd", Fd),
+    ("offset", Off),
+];
+const SOCKNAME: &[(&str, ArgType)] = &[("sockfd", Fd), ("addr", SockaddrOut(2)), ("addrlen", Ptr)];
+const READV: &[(&str, ArgType)] = &[("fd", Fd), ("iov", IovecOut(2)), ("iovcnt", Int)];
+const WRITEV: &[(&str, ArgType)] = &[("fd", Fd), ("iov", IovecIn(2)), ("iovcnt", Int)];
+const PREADV: &[(&str, ArgType)] = &[("fd", Fd), ("iov", IovecOut(2)), ("iovcnt", Int), ("pos_l", Off), ("pos_h", Off)];
+const PWRITEV: &[(&str, ArgType)] = &[("fd", Fd), ("iov", IovecIn(2)), ("iovcnt", Int), ("pos_l", Off), ("pos_h", Off)];
+const PREADV2: &[(&str, ArgType)] =
+    &[("fd", Fd), ("iov", IovecOut(2)), ("iovcnt", Int), ("pos_l", Off), ("pos_h", Off), ("flags", Hex)];
+const PWRITEV2: &[(&str, ArgType)] =
+    &[("fd", Fd), ("iov", IovecIn(2)), ("iovcnt", Int), ("pos_l", Off), ("pos_h", Off), ("flags", Hex)];
+const PROCESS_VM: &[(&str, ArgType)] = &[
+    ("pid", Pid),
+    ("local_iov", Ptr),
+    ("liovcnt", Uint),
+    ("remote_iov", Ptr),
+    ("riovcnt", Uint),
+    ("flags", Hex),
+];
+const NO_ARGS: &[(&str, ArgType)] = &[];
+const ONE_UID: &[(&str, ArgType)] = &[("id", Uid)];
+const TWO_UIDS: &[(&str, ArgType)] = &[("real", Uid), ("effective", Uid)];
+const THREE_UIDS: &[(&str, ArgType)] = &[("real", Uid), ("effective", Uid), ("saved", Uid)];
+const THREE_UID_PTRS: &[(&str, ArgType)] = &[("real", Ptr), ("effective", Ptr), ("saved", Ptr)];
+const CHOWN: &[(&str, ArgType)] = &[("pathname", Path), ("owner", Uid), ("group", Uid)];
+const FCHOWN: &[(&str, ArgType)] = &[("fd", Fd), ("owner", Uid), ("group", Uid)];
+const ONLY_FD: &[(&str, ArgType)] = &[("fd", Fd)];
+const ONLY_PATH: &[(&str, ArgType)] = &[("pathname", Path)];
+const ONLY_PID: &[(&str, ArgType)] = &[("pid", Pid)];
+const ONLY_STATUS: &[(&str, ArgType)] = &[("status", Int)];
+const PRIO_POLICY: &[(&str, ArgType)] = &[("policy", Int)];
+const SCHED_PARAM: &[(&str, ArgType)] = &[("pid", Pid), ("param", Ptr)];
+const ADDR_LEN: &[(&str, ArgType)] = &[("addr", Ptr), ("len", Size)];
+const CLOCK_TS_IN: &[(&str, ArgType)] = &[("clockid", Clock), ("tp", In(S::Timespec))];
+const CLOCK_TS_OUT: &[(&str, ArgType)] = &[("clockid", Clock), ("tp", Out(S::Timespec))];
+const CLOCK_NANOSLEEP: &[(&str, ArgType)] =
+    &[("clockid", Clock), ("flags", Hex), ("request", In(S::Timespec)), ("remain", Out(S::Timespec))];
+const FUTEX: &[(&str, ArgType)] = &[
+    ("uaddr", Ptr),
+    ("futex_op", Flags(F::Futex)),
+    ("val", Uint),
+    ("timeout", Ptr),
+    ("uaddr2", Ptr),
+    ("val3", Uint),
+];
+const TIMER_ID: &[(&str, ArgType)] = &[("timerid", Int)];
+const SIGSET_SIZE: &[(&str, ArgType)] = &[("set", Out(S::Sigset)), ("sigsetsize", Size)];
+const SIGTIMEDWAIT: &[(&str, ArgType)] =
+    &[("set", In(S::Sigset)), ("info", Ptr), ("timeout", In(S::Timespec)), ("sigsetsize", Size)];
+const PSELECT: &[(&str, ArgType)] = &[
+    ("nfds", Int),
+    ("readfds", Ptr),
+    ("writefds", Ptr),
+    ("exceptfds", Ptr),
+    ("timeout", In(S::Timespec)),
+    ("sigmask", Ptr),
+];
+const PPOLL: &[(&str, ArgType)] =
+    &[("fds", Pollfds(1)), ("nfds", Uint), ("tmo_p", In(S::Timespec)), ("sigmask", In(S::Sigset)), ("sigsetsize", Size)];
+const IO_GETEVENTS: &[(&str, ArgType)] =
+    &[("ctx_id", Hex), ("min_nr", Int), ("nr", Int), ("events", Ptr), ("timeout", In(S::Timespec))];
+const IO_PGETEVENTS: &[(&str, ArgType)] =
+    &[("ctx_id", Hex), ("min_nr", Int), ("nr", Int), ("events", Ptr), ("timeout", In(S::Timespec)), ("usig", Ptr)];
+const RECVMMSG: &[(&str, ArgType)] =
+    &[("sockfd", Fd), ("msgvec", Ptr), ("vlen", Uint), ("flags", Flags(F::Msg)), ("timeout", In(S::Timespec))];
+const MQ_TIMEDSEND: &[(&str, ArgType)] =
+    &[("mqdes", Fd), ("msg_ptr", BufIn(2)), ("msg_len", Size), ("msg_prio", Uint), ("abs_timeout", In(S::Timespec))];
+const MQ_TIMEDRECEIVE: &[(&str, ArgType)] =
+    &[("mqdes", Fd), ("msg_ptr", BufOut(2)), ("msg_len", Size), ("msg_prio", Ptr), ("abs_timeout", In(S::Timespec))];
+const SEMTIMEDOP: &[(&str, ArgType)] = &[("semid", Int), ("sops", Ptr), ("nsops", Size), ("timeout", In(S::Timespec))];
+const TIMERFD_SETTIME: &[(&str, ArgType)] = &[("fd", Fd), ("flags", Hex), ("new_value", Ptr), ("old_value", Ptr)];
+const TIMERFD_GETTIME: &[(&str, ArgType)] = &[("fd", Fd), ("curr_value", Ptr)];
+const TIMER_SETTIME: &[(&str, ArgType)] = &[("timerid", Int), ("flags", Hex), ("new_value", Ptr), ("old_value", Ptr)];
+const TIMER_GETTIME: &[(&str, ArgType)] = &[("timerid", Int), ("curr_value", Ptr)];
+const UTIMENSAT: &[(&str, ArgType)] = &[("dirfd", DirFd), ("pathname", Path), ("times", Ptr), ("flags", Flags(F::At))];
+const STAT_AT: &[(&str, ArgType)] =
+    &[("dirfd", DirFd), ("pathname", Path), ("statbuf", Out(S::Stat)), ("flags", Flags(F::At))];
+const FCNTL: &[(&str, ArgType)] = &[("fd", Fd), ("cmd", Flags(F::Fcntl)), ("arg", Hex)];
+const GETRLIMIT: &[(&str, ArgType)] = &[("resource", Flags(F::Rlimit)), ("rlim", Out(S::Rlimit))];
+const SENDFILE: &[(&str, ArgType)] = &[("out_fd", Fd), ("in_fd", Fd), ("offset", Ptr), ("count", Size)];
+const TRUNCATE: &[(&str, ArgType)] = &[("path", Path), ("length", Off)];
+const FTRUNCATE: &[(&str, ArgType)] = &[("fd", Fd), ("length", Off)];
+const STATFS: &[(&str, ArgType)] = &[("path", Path), ("buf", Out(S::Statfs))];
+const FSTATFS: &[(&str, ArgType)] = &[("fd", Fd), ("buf", Out(S::Statfs))];
+const WAIT4: &[(&str, ArgType)] =
+    &[("pid", Pid), ("wstatus", Out(S::WaitStatus)), ("options", Flags(F::Wait)), ("rusage", Out(S::Rusage))];
+
+#[rustfmt::skip]
+static SPECS: &[SyscallSpec] = &[
+    // --- E/S básica y archivos ---
+    sc("read", &[("fd", Fd), ("buf", BufOut(2)), ("count", Size)], R::Int),
+    sc("write", &[("fd", Fd), ("buf", BufIn(2)), ("count", Size)], R::Int),
+    sc("open", &[("pathname", Path), ("flags", Flags(F::Open)), ("mode", Mode)], R::Fd),
+    sc("openat", &[("dirfd", DirFd), ("pathname", Path), ("flags", Flags(F::Open)), ("mode", Mode)], R::Fd),
+    sc("openat2", &[("dirfd", DirFd), ("pathname", Path), ("how", Ptr), ("size", Size)], R::Fd),
+    sc("creat", &[("pathname", Path), ("mode", Mode)], R::Fd),
+    sc("close", ONLY_FD, R::Int),
+    sc("close_range", &[("first", Fd), ("last", Uint), ("flags", Flags(F::CloseRange))], R::Int),
+    sc("stat", STAT_PATH, R::Int),
+    sc("lstat", STAT_PATH, R::Int),
+    sc("fstat", &[("fd", Fd), ("statbuf", Out(S::Stat))], R::Int),
+    sc("newfstatat", STAT_AT, R::Int),
+    sc("statx", &[("dirfd", DirFd), ("pathname", Path), ("flags", Flags(F::At)), ("mask", Hex), ("statxbuf", Out(S::Statx))], R::Int),
+    sc("lseek", &[("fd", Fd), ("offset", Off), ("whence", Flags(F::Whence))], R::Int),
+    sc("pread64", &[("fd", Fd), ("buf", BufOut(2)), ("count", Size), ("offset", Off)], R::Int),
+    sc("pwrite64", &[("fd", Fd), ("buf", BufIn(2)), ("count", Size), ("offset", Off)], R::Int),
+    sc("readv", READV, R::Int),
+    sc("writev", WRITEV, R::Int),
+    sc("preadv", PREADV, R::Int),
+    sc("pwritev", PWRITEV, R::Int),
+    sc("preadv2", PREADV2, R::Int),
+    sc("pwritev2", PWRITEV2, R::Int),
+    sc("access", &[("pathname", Path), ("mode", Flags(F::Access))], R::Int),
+    sc("faccessat", &[("dirfd", DirFd), ("pathname", Path), ("mode", Flags(F::Access))], R::Int),
+    sc("faccessat2", &[("dirfd", DirFd), ("pathname", Path), ("mode", Flags(F::Access)), ("flags", Flags(F::At))], R::Int),
+    sc("pipe", &[("pipefd", Out(S::FdPair))], R::Int),
+    sc("pipe2", &[("pipefd", Out(S::FdPair)), ("flags", Flags(F::Open))], R::Int),
+    sc("dup", &[("oldfd", Fd)], R::Fd),
+    sc("dup2", &[("oldfd", Fd), ("newfd", Fd)], R::Fd),
+    sc("dup3", &[("oldfd", Fd), ("newfd", Fd), ("flags", Flags(F::Open))], R::Fd),
+    sc("fcntl", FCNTL, R::Int),
+    sc("flock", &[("fd", Fd), ("operation", Flags(F::Flock))], R::Int),
+    sc("fsync", ONLY_FD, R::Int),
+    sc("fdatasync", ONLY_FD, R::Int),
+    sc("sync", NO_ARGS, R::Int),
+    sc("syncfs", ONLY_FD, R::Int),
+    sc("sync_file_range", &[("fd", Fd), ("offset", Off), ("nbytes", Off), ("flags", Hex)], R::Int),
+    sc("truncate", TRUNCATE, R::Int),
+    sc("ftruncate", FTRUNCATE, R::Int),
+    sc("fallocate", &[("fd", Fd), ("mode", Hex), ("offset", Off), ("len", Off)], R::Int),
+    sc("fadvise64", &[("fd", Fd), ("offset", Off), ("len", Size), ("advice", Int)], R::Int),
+    sc("readahead", &[("fd", Fd), ("offset", Off), ("count", Size)], R::Int),
+    sc("getdents", &[("fd", Fd), ("dirp", Ptr), ("count", Uint)], R::Int),
+    sc("getdents64", &[("fd", Fd), ("dirp", Out(S::Dirents64)), ("count", Uint)], R::Int),
+    sc("getcwd", &[("buf", Out(S::CStr)), ("size", Size)], R::Int),
+    sc("chdir", ONLY_PATH, R::Int),
+    sc("fchdir", ONLY_FD, R::Int),
+    sc("chroot", ONLY_PATH, R::Int),
+    sc("pivot_root", &[("new_root", Path), ("put_old", Path)], R::Int),
+    sc("rename", &[("oldpath", Path), ("newpath", Path)], R::Int),
+    sc("renameat", &[("olddirfd", DirFd), ("oldpath", Path), ("newdirfd", DirFd), ("newpath", Path)], R::Int),
+    sc("renameat2", &[("olddirfd", DirFd), ("oldpath", Path), ("newdirfd", DirFd), ("newpath", Path), ("flags", Flags(F::Rename))], R::Int),
+    sc("mkdir", &[("pathname", Path), ("mode", Mode)], R::Int),
+    sc("mkdirat", &[("dirfd", DirFd), ("pathname", Path), ("mode", Mode)], R::Int),
+    sc("rmdir", ONLY_PATH, R::Int),
+    sc("link", &[("oldpath", Path), ("newpath", Path)], R::Int),
+    sc("linkat", &[("olddirfd", DirFd), ("oldpath", Path), ("newdirfd", DirFd), ("newpath", Path), ("flags", Flags(F::At))], R::Int),
+    sc("unlink", ONLY_PATH, R::Int),
+    sc("unlinkat", &[("dirfd", DirFd), ("pathname", Path), ("flags", Flags(F::At))], R::Int),
+    sc("symlink", &[("target", Path), ("linkpath", Path)], R::Int),
+    sc("symlinkat", &[("target", Path), ("newdirfd", DirFd), ("linkpath", Path)], R::Int),
+    sc("readlink", &[("pathname", Path), ("buf", BufOut(2)), ("bufsiz", Size)], R::Int),
+    sc("readlinkat", &[("dirfd", DirFd), ("pathname", Path), ("buf", BufOut(3)), ("bufsiz", Size)], R::Int),
+    sc("mknod", &[("pathname", Path), ("mode", Mode), ("dev", Hex)], R::Int),
+    sc("mknodat", &[("dirfd", DirFd), ("pathname", Path), ("mode", Mode), ("dev", Hex)], R::Int),
+    sc("chmod", &[("pathname", Path), ("mode", Mode)], R::Int),
+    sc("fchmod", &[("fd", Fd), ("mode", Mode)], R::Int),
+    sc("fchmodat", &[("dirfd", DirFd), ("pathname", Path), ("mode", Mode)], R::Int),
+    sc("fchmodat2", &[("dirfd", DirFd), ("pathname", Path), ("mode", Mode), ("flags", Flags(F::At))], R::Int),
+    sc("chown", CHOWN, R::Int),
+    sc("lchown", CHOWN, R::Int),
+    sc("fchown", FCHOWN, R::Int),
+    sc("fchownat", &[("dirfd", DirFd), ("pathname", Path), ("owner", Uid), ("group", Uid), ("flags", Flags(F::At))], R::Int),
+    sc("umask", &[("mask", Mode)], R::Mode),
+    sc("utime", &[("filename", Path), ("times", Ptr)], R::Int),
+    sc("utimes", &[("filename", Path), ("times", Ptr)], R::Int),
+    sc("futimesat", &[("dirfd", DirFd), ("pathname", Path), ("times", Ptr)], R::Int),
+    sc("utimensat", UTIMENSAT, R::Int),
+    sc("statfs", STATFS, R::Int),
+    sc("fstatfs", FSTATFS, R::Int),
+    sc("ustat", &[("dev", Hex), ("ubuf", Ptr)], R::Int),
+    sc("sendfile", SENDFILE, R::Int),
+    sc("splice", &[("fd_in", Fd), ("off_in", Ptr), ("fd_out", Fd), ("off_out", Ptr), ("len", Size), ("flags", Hex)], R::Int),
+    sc("tee", &[("fd_in", Fd), ("fd_out", Fd), ("len", Size), ("flags", Hex)], R::Int),
+    sc("vmsplice", &[("fd", Fd), ("iov", IovecIn(2)), ("nr_segs", Uint), ("flags", Hex)], R::Int),
+    sc("copy_file_range", &[("fd_in", Fd), ("off_in", Ptr), ("fd_out", Fd), ("off_out", Ptr), ("len", Size), ("flags", Hex)], R::Int),
+    sc("ioctl", &[("fd", Fd), ("request", Flags(F::Ioctl)), ("arg", Hex)], R::Int),
+    sc("name_to_handle_at", &[("dirfd", DirFd), ("pathname", Path), ("handle", Ptr), ("mount_id", Ptr), ("flags", Flags(F::At))], R::Int),
+    sc("open_by_handle_at", &[("mount_fd", Fd), ("handle", Ptr), ("flags", Flags(F::Open))], R::Fd),
+    sc("memfd_create", &[("name", Str), ("flags", Hex)], R::Fd),
+    sc("memfd_secret", &[("flags", Hex)], R::Fd),
+    sc("cachestat", &[("fd", Fd), ("cstat_range", Ptr), ("cstat", Ptr), ("flags", Hex)], R::Int),
+
+    // --- Atributos extendidos ---
+    sc("setxattr", XATTR_SET, R::Int),
+    sc("lsetxattr", XATTR_SET, R::Int),
+    sc("fsetxattr", &[("fd", Fd), ("name", Str), ("value", BufIn(3)), ("size", Size), ("flags", Hex)], R::Int),
+    sc("getxattr", XATTR_GET, R::Int),
+    sc("lgetxattr", XATTR_GET, R::Int),
+    sc("fgetxattr", &[("fd", Fd), ("name", Str), ("value", BufOut(3)), ("size", Size)], R::Int),
+    sc("listxattr", XATTR_LIST, R::Int),
+    sc("llistxattr", XATTR_LIST, R::Int),
+    sc("flistxattr", &[("fd", Fd), ("list", Ptr), ("size", Size)], R::Int),
+    sc("removexattr", XATTR_REMOVE, R::Int),
+    sc("lremovexattr", XATTR_REMOVE, R::Int),
+    sc("fremovexattr", &[("fd", Fd), ("name", Str)], R::Int),
+
+    // --- Memoria ---
+    sc("mmap", MMAP, R::Hex),
+    sc("mprotect", &[("addr", Ptr), ("len", Size), ("prot", Flags(F::MmapProt))], R::Int),
+    sc("pkey_mprotect", &[("addr", Ptr), ("len", Size), ("prot", Flags(F::MmapProt)), ("pkey", Int)], R::Int),
+    sc("pkey_alloc", &[("flags", Hex), ("access_rights", Hex)], R::Int),
+    sc("pkey_free", &[("pkey", Int)], R::Int),
+    sc("munmap", ADDR_LEN, R::Int),
+    sc("brk", &[("addr", Ptr)], R::Hex),
+    sc("mremap", &[("old_address", Ptr), ("old_size", Size), ("new_size", Size), ("flags", Flags(F::Mremap)), ("new_address", Ptr)], R::Hex),
+    sc("msync", &[("addr", Ptr), ("length", Size), ("flags", Flags(F::Msync))], R::Int),
+    sc("mincore", &[("addr", Ptr), ("length", Size), ("vec", Ptr)], R::Int),
+    sc("madvise", &[("addr", Ptr), ("length", Size), ("advice", Flags(F::Madvise))], R::Int),
+    sc("process_madvise", &[("pidfd", Fd), ("iovec", Ptr), ("vlen", Size), ("advice", Flags(F::Madvise)), ("flags", Hex)], R::Int),
+    sc("mlock", ADDR_LEN, R::Int),
+    sc("mlock2", &[("addr", Ptr), ("len", Size), ("flags", Hex)], R::Int),
+    sc("munlock", ADDR_LEN, R::Int),
+    sc("mlockall", &[("flags", Hex)], R::Int),
+    sc("munlockall", NO_ARGS, R::Int),
+    sc("remap_file_pages", &[("addr", Ptr), ("size", Size), ("prot", Hex), ("pgoff", Uint), ("flags", Hex)], R::Int),
+    sc("mbind", &[("addr", Ptr), ("len", Size), ("mode", Int), ("nodemask", Ptr), ("maxnode", Uint), ("flags", Hex)], R::Int),
+    sc("set_mempolicy", &[("mode", Int), ("nodemask", Ptr), ("maxnode", Uint)], R::Int),
+    sc("get_mempolicy", &[("mode", Ptr), ("nodemask", Ptr), ("maxnode", Uint), ("addr", Ptr), ("flags", Hex)], R::Int),
+    sc("set_mempolicy_home_node", &[("start", Ptr), ("len", Size), ("home_node", Uint), ("flags", Hex)], R::Int),
+    sc("migrate_pages", &[("pid", Pid), ("maxnode", Uint), ("old_nodes", Ptr), ("new_nodes", Ptr)], R::Int),
+    sc("move_pages", &[("pid", Pid), ("count", Uint), ("pages", Ptr), ("nodes", Ptr), ("status", Ptr), ("flags", Hex)], R::Int),
+    sc("map_shadow_stack", &[("addr", Ptr), ("size", Size), ("flags", Hex)], R::Hex),
+    sc("mseal", &[("addr", Ptr), ("len", Size), ("flags", Hex)], R::Int),
+    sc("userfaultfd", &[("flags", Flags(F::Open))], R::Fd),
+    sc("membarrier", &[("cmd", Int), ("flags", Hex), ("cpu_id", Int)], R::Int),
+    sc("process_vm_readv", PROCESS_VM, R::Int),
+    sc("process_vm_writev", PROCESS_VM, R::Int),
+    sc("process_mrelease", &[("pidfd", Fd), ("flags", Hex)], R::Int),
+
+    // --- Procesos ---
+    sc("clone", &[("flags", Flags(F::Clone)), ("stack", Ptr), ("parent_tid", Ptr), ("child_tid", Ptr), ("tls", Hex)], R::Pid),
+    sc("clone3", &[("cl_args", Ptr), ("size", Size)], R::Pid),
+    sc("fork", NO_ARGS, R::Pid),
+    sc("vfork", NO_ARGS, R::Pid),
+    sc("execve", &[("pathname", Path), ("argv", Argv), ("envp", Ptr)], R::Int),
+    sc("execveat", &[("dirfd", DirFd), ("pathname", Path), ("argv", Argv), ("envp", Ptr), ("flags", Flags(F::At))], R::Int),
+    sc("exit", ONLY_STATUS, R::Int),
+    sc("exit_group", ONLY_STATUS, R::Int),
+    sc("wait4", WAIT4, R::Pid),
+    sc("waitid", &[("idtype", Int), ("id", Pid), ("infop", Ptr), ("options", Flags(F::Wait)), ("rusage", Out(S::Rusage))], R::Int),
+    sc("getpid", NO_ARGS, R::Pid),
+    sc("getppid", NO_ARGS, R::Pid),
+    sc("gettid", NO_ARGS, R::Pid),
+    sc("getpgrp", NO_ARGS, R::Pid),
+    sc("getpgid", ONLY_PID, R::Pid),
+    sc("setpgid", &[("pid", Pid), ("pgid", Pid)], R::Int),
+    sc("getsid", ONLY_PID, R::Pid),
+    sc("setsid", NO_ARGS, R::Pid),
+    sc("set_tid_address", &[("tidptr", Ptr)], R::Pid),
+    sc("set_robust_list", &[("head", Ptr), ("len", Size)], R::Int),
+    sc("get_robust_list", &[("pid", Pid), ("head_ptr", Ptr), ("len_ptr", Ptr)], R::Int),
+    sc("rseq", &[("rseq", Ptr), ("rseq_len", Uint), ("flags", Hex), ("sig", Hex)], R::Int),
+    sc("unshare", &[("flags", Flags(F::Clone))], R::Int),
+    sc("setns", &[("fd", Fd), ("nstype", Flags(F::Clone))], R::Int),
+    sc("personality", &[("persona", Hex)], R::Hex),
+    sc("prctl", &[("option", Flags(F::Prctl)), ("arg2", Hex), ("arg3", Hex), ("arg4", Hex), ("arg5", Hex)], R::Int),
+    sc("arch_prctl", &[("code", Flags(F::ArchPrctl)), ("addr", Hex)], R::Int),
+    sc("ptrace", &[("request", Int), ("pid", Pid), ("addr", Ptr), ("data", Hex)], R::Int),
+    sc("kcmp", &[("pid1", Pid), ("pid2", Pid), ("type", Int), ("idx1", Uint), ("idx2", Uint)], R::Int),
+    sc("pidfd_open", &[("pid", Pid), ("flags", Hex)], R::Fd),
+    sc("pidfd_getfd", &[("pidfd", Fd), ("targetfd", Int), ("flags", Hex)], R::Fd),
+    sc("seccomp", &[("operation", Int), ("flags", Hex), ("args", Ptr)], R::Int),
+    sc("getrlimit", GETRLIMIT, R::Int),
+    sc("setrlimit", &[("resource", Flags(F::Rlimit)), ("rlim", In(S::Rlimit))], R::Int),
+    sc("prlimit64", &[("pid", Pid), ("resource", Flags(F::Rlimit)), ("new_limit", In(S::Rlimit)), ("old_limit", Out(S::Rlimit))], R::Int),
+    sc("getrusage", &[("who", Int), ("usage", Out(S::Rusage))], R::Int),
+    sc("times", &[("buf", Out(S::Tms))], R::Int),
+    sc("getpriority", &[("which", Int), ("who", Int)], R::Int),
+    sc("setpriority", &[("which", Int), ("who", Int), ("prio", Int)], R::Int),
+    sc("ioprio_set", &[("which", Int), ("who", Int), ("ioprio", Int)], R::Int),
+    sc("ioprio_get", &[("which", Int), ("who", Int)], R::Int),
+    sc("capget", &[("hdrp", Ptr), ("datap", Ptr)], R::Int),
+    sc("capset", &[("hdrp", Ptr), ("datap", Ptr)], R::Int),
+    sc("landlock_create_ruleset", &[("attr", Ptr), ("size", Size), ("flags", Hex)], R::Fd),
+    sc("landlock_add_rule", &[("ruleset_fd", Fd), ("rule_type", Int), ("rule_attr", Ptr), ("flags", Hex)], R::Int),
+    sc("landlock_restrict_self", &[("ruleset_fd", Fd), ("flags", Hex)], R::Int),
+    sc("lsm_get_self_attr", &[("attr", Uint), ("ctx", Ptr), ("size", Ptr), ("flags", Hex)], R::Int),
+    sc("lsm_set_self_attr", &[("attr", Uint), ("ctx", Ptr), ("size", Size), ("flags", Hex)], R::Int),
+    sc("lsm_list_modules", &[("ids", Ptr), ("size", Ptr), ("flags", Hex)], R::Int),
+
+    // --- Credenciales ---
+    sc("getuid", NO_ARGS, R::Int),
+    sc("geteuid", NO_ARGS, R::Int),
+    sc("getgid", NO_ARGS, R::Int),
+    sc("getegid", NO_ARGS, R::Int),
+    sc("setuid", ONE_UID, R::Int),
+    sc("setgid", ONE_UID, R::Int),
+    sc("setfsuid", ONE_UID, R::Int),
+    sc("setfsgid", ONE_UID, R::Int),
+    sc("setreuid", TWO_UIDS, R::Int),
+    sc("setregid", TWO_UIDS, R::Int),
+    sc("setresuid", THREE_UIDS, R::Int),
+    sc("setresgid", THREE_UIDS, R::Int),
+    sc("getresuid", THREE_UID_PTRS, R::Int),
+    sc("getresgid", THREE_UID_PTRS, R::Int),
+    sc("getgroups", &[("size", Int), ("list", Ptr)], R::Int),
+    sc("setgroups", &[("size", Size), ("list", Ptr)], R::Int),
+
+    // --- Planificación ---
+    sc("sched_yield", NO_ARGS, R::Int),
+    sc("sched_setparam", SCHED_PARAM, R::Int),
+    sc("sched_getparam", SCHED_PARAM, R::Int),
+    sc("sched_setscheduler", &[("pid", Pid), ("policy", Int), ("param", Ptr)], R::Int),
+    sc("sched_getscheduler", ONLY_PID, R::Int),
+    sc("sched_get_priority_max", PRIO_POLICY, R::Int),
+    sc("sched_get_priority_min", PRIO_POLICY, R::Int),
+    sc("sched_rr_get_interval", &[("pid", Pid), ("tp", Out(S::Timespec))], R::Int),
+    sc("sched_setaffinity", &[("pid", Pid), ("cpusetsize", Size), ("mask", Ptr)], R::Int),
+    sc("sched_getaffinity", &[("pid", Pid), ("cpusetsize", Size), ("mask", Ptr)], R::Int),
+    sc("sched_setattr", &[("pid", Pid), ("attr", Ptr), ("flags", Hex)], R::Int),
+    sc("sched_getattr", &[("pid", Pid), ("attr", Ptr), ("size", Uint), ("flags", Hex)], R::Int),
+    sc("getcpu", &[("cpu", Ptr), ("node", Ptr), ("tcache", Ptr)], R::Int),
+
+    // --- Tiempo ---
+    sc("nanosleep", &[("req", In(S::Timespec)), ("rem", Out(S::Timespec))], R::Int),
+    sc("clock_gettime", CLOCK_TS_OUT, R::Int),
+    sc("clock_settime", CLOCK_TS_IN, R::Int),
+    sc("clock_getres", CLOCK_TS_OUT, R::Int),
+    sc("clock_nanosleep", CLOCK_NANOSLEEP, R::Int),
+    sc("clock_adjtime", &[("clk_id", Clock), ("buf", Ptr)], R::Int),
+    sc("gettimeofday", &[("tv", Out(S::Timeval)), ("tz", Ptr)], R::Int),
+    sc("settimeofday", &[("tv", In(S::Timeval)), ("tz", Ptr)], R::Int),
+    sc("adjtimex", &[("buf", Ptr)], R::Int),
+    sc("time", &[("tloc", Ptr)], R::Int),
+    sc("alarm", &[("seconds", Uint)], R::Int),
+    sc("getitimer", &[("which", Int), ("curr_value", Out(S::Itimerval))], R::Int),
+    sc("setitimer", &[("which", Int), ("new_value", In(S::Itimerval)), ("old_value", Out(S::Itimerval))], R::Int),
+    sc("timer_create", &[("clockid", Clock), ("sevp", Ptr), ("timerid", Ptr)], R::Int),
+    sc("timer_settime", TIMER_SETTIME, R::Int),
+    sc("timer_gettime", TIMER_GETTIME, R::Int),
+    sc("timer_getoverrun", TIMER_ID, R::Int),
+    sc("timer_delete", TIMER_ID, R::Int),
+    sc("timerfd_create", &[("clockid", Clock), ("flags", Flags(F::Open))], R::Fd),
+    sc("timerfd_settime", TIMERFD_SETTIME, R::Int),
+    sc("timerfd_gettime", TIMERFD_GETTIME, R::Int),
+
+    // --- Señales ---
+    sc("kill", &[("pid", Pid), ("sig", Signal)], R::Int),
+    sc("tkill", &[("tid", Pid), ("sig", Signal)], R::Int),
+    sc("tgkill", &[("tgid", Pid), ("tid", Pid), ("sig", Signal)], R::Int),
+    sc("rt_sigaction", &[("signum", Signal), ("act", In(S::Sigaction)), ("oldact", Out(S::Sigaction)), ("sigsetsize", Size)], R::Int),
+    sc("rt_sigprocmask", &[("how", Flags(F::SigHow)), ("set", In(S::Sigset)), ("oldset", Out(S::Sigset)), ("sigsetsize", Size)], R::Int),
+    sc("rt_sigreturn", NO_ARGS, R::Int),
+    sc("rt_sigpending", SIGSET_SIZE, R::Int),
+    sc("rt_sigtimedwait", SIGTIMEDWAIT, R::Int),
+    sc("rt_sigqueueinfo", &[("tgid", Pid), ("sig", Signal), ("info", Ptr)], R::Int),
+    sc("rt_tgsigqueueinfo", &[("tgid", Pid), ("tid", Pid), ("sig", Signal), ("info", Ptr)], R::Int),
+    sc("rt_sigsuspend", &[("mask", In(S::Sigset)), ("sigsetsize", Size)], R::Int),
+    sc("sigaltstack", &[("ss", Ptr), ("old_ss", Ptr)], R::Int),
+    sc("pause", NO_ARGS, R::Int),
+    sc("signalfd", &[("fd", Fd), ("mask", In(S::Sigset)), ("sizemask", Size)], R::Fd),
+    sc("signalfd4", &[("fd", Fd), ("mask", In(S::Sigset)), ("sizemask", Size), ("flags", Flags(F::Open))], R::Fd),
+    sc("pidfd_send_signal", &[("pidfd", Fd), ("sig", Signal), ("info", Ptr), ("flags", Hex)], R::Int),
+    sc("restart_syscall", NO_ARGS, R::Int),
+
+    // --- Red ---
+    sc("socket", &[("domain", Flags(F::SockDomain)), ("type", Flags(F::SockType)), ("protocol", Int)], R::Fd),
+    sc("socketpair", &[("domain", Flags(F::SockDomain)), ("type", Flags(F::SockType)), ("protocol", Int), ("sv", Out(S::FdPair))], R::Int),
+    sc("connect", &[("sockfd", Fd), ("addr", Sockaddr(2)), ("addrlen", Uint)], R::Int),
+    sc("bind", &[("sockfd", Fd), ("addr", Sockaddr(2)), ("addrlen", Uint)], R::Int),
+    sc("listen", &[("sockfd", Fd), ("backlog", Int)], R::Int),
+    sc("accept", SOCKNAME, R::Fd),
+    sc("accept4", &[("sockfd", Fd), ("addr", SockaddrOut(2)), ("addrlen", Ptr), ("flags", Flags(F::SockType))], R::Fd),
+    sc("getsockname", SOCKNAME, R::Int),
+    sc("getpeername", SOCKNAME, R::Int),
+    sc("shutdown", &[("sockfd", Fd), ("how", Flags(F::Shutdown))], R::Int),
+    sc("sendto", &[("sockfd", Fd), ("buf", BufIn(2)), ("len", Size), ("flags", Flags(F::Msg)), ("dest_addr", Sockaddr(5)), ("addrlen", Uint)], R::Int),
+    sc("recvfrom", &[("sockfd", Fd), ("buf", BufOut(2)), ("len", Size), ("flags", Flags(F::Msg)), ("src_addr", SockaddrOut(5)), ("addrlen", Ptr)], R::Int),
+    sc("sendmsg", &[("sockfd", Fd), ("msg", Ptr), ("flags", Flags(F::Msg))], R::Int),
+    sc("recvmsg", &[("sockfd", Fd), ("msg", Ptr), ("flags", Flags(F::Msg))], R::Int),
+    sc("sendmmsg", &[("sockfd", Fd), ("msgvec", Ptr), ("vlen", Uint), ("flags", Flags(F::Msg))], R::Int),
+    sc("recvmmsg", RECVMMSG, R::Int),
+    sc("setsockopt", &[("sockfd", Fd), ("level", Int), ("optname", Int), ("optval", Ptr), ("optlen", Uint)], R::Int),
+    sc("getsockopt", &[("sockfd", Fd), ("level", Int), ("optname", Int), ("optval", Ptr), ("optlen", Ptr)], R::Int),
+
+    // --- Multiplexación y notificaciones ---
+    sc("poll", &[("fds", Pollfds(1)), ("nfds", Uint), ("timeout", Int)], R::Int),
+    sc("ppoll", PPOLL, R::Int),
+    sc("select", &[("nfds", Int), ("readfds", Ptr), ("writefds", Ptr), ("exceptfds", Ptr), ("timeout", In(S::Timeval))], R::Int),
+    sc("pselect6", PSELECT, R::Int),
+    sc("epoll_create", &[("size", Int)], R::Fd),
+    sc("epoll_create1", &[("flags", Flags(F::Open))], R::Fd),
+    sc("epoll_ctl", &[("epfd", Fd), ("op", Flags(F::EpollCtl)), ("fd", Fd), ("event", Ptr)], R::Int),
+    sc("epoll_wait", &[("epfd", Fd), ("events", Ptr), ("maxevents", Int), ("timeout", Int)], R::Int),
+    sc("epoll_pwait", &[("epfd", Fd), ("events", Ptr), ("maxevents", Int), ("timeout", Int), ("sigmask", In(S::Sigset)), ("sigsetsize", Size)], R::Int),
+    sc("epoll_pwait2", &[("epfd", Fd), ("events", Ptr), ("maxevents", Int), ("timeout", In(S::Timespec)), ("sigmask", In(S::Sigset)), ("sigsetsize", Size)], R::Int),
+    sc("eventfd", &[("initval", Uint)], R::Fd),
+    sc("eventfd2", &[("initval", Uint), ("flags", Flags(F::Open))], R::Fd),
+    sc("inotify_init", NO_ARGS, R::Fd),
+    sc("inotify_init1", &[("flags", Flags(F::Open))], R::Fd),
+    sc("inotify_add_watch", &[("fd", Fd), ("pathname", Path), ("mask", Hex)], R::Int),
+    sc("inotify_rm_watch", &[("fd", Fd), ("wd", Int)], R::Int),
+    sc("fanotify_init", &[("flags", Hex), ("event_f_flags", Flags(F::Open))], R::Fd),
+    sc("fanotify_mark", &[("fanotify_fd", Fd), ("flags", Hex), ("mask", Hex), ("dirfd", DirFd), ("pathname", Path)], R::Int),
+
+    // --- Futex ---
+    sc("futex", FUTEX, R::Int),
+    sc("futex_waitv", &[("waiters", Ptr), ("nr_futexes", Uint), ("flags", Hex), ("timeout", In(S::Timespec)), ("clockid", Clock)], R::Int),
+    sc("futex_wake", &[("uaddr", Ptr), ("mask", Hex), ("nr", Int), ("flags", Hex)], R::Int),
+    sc("futex_wait", &[("uaddr", Ptr), ("val", Hex), ("mask", Hex), ("flags", Hex), ("timeout", In(S::Timespec)), ("clockid", Clock)], R::Int),
+    sc("futex_requeue", &[("waiters", Ptr), ("flags", Hex), ("nr_wake", Int), ("nr_requeue", Int)], R::Int),
+
+    // --- IPC System V y POSIX ---
+    sc("shmget", &[("key", Hex), ("size", Size), ("shmflg", Hex)], R::Int),
+    sc("shmat", &[("shmid", Int), ("shmaddr", Ptr), ("shmflg", Hex)], R::Hex),
+    sc("shmctl", &[("shmid", Int), ("cmd", Int), ("buf", Ptr)], R::Int),
+    sc("shmdt", &[("shmaddr", Ptr)], R::Int),
+    sc("semget", &[("key", Hex), ("nsems", Int), ("semflg", Hex)], R::Int),
+    sc("semop", &[("semid", Int), ("sops", Ptr), ("nsops", Size)], R::Int),
+    sc("semtimedop", SEMTIMEDOP, R::Int),
+    sc("semctl", &[("semid", Int), ("semnum", Int), ("cmd", Int), ("arg", Hex)], R::Int),
+    sc("msgget", &[("key", Hex), ("msgflg", Hex)], R::Int),
+    sc("msgsnd", &[("msqid", Int), ("msgp", Ptr), ("msgsz", Size), ("msgflg", Hex)], R::Int),
+    sc("msgrcv", &[("msqid", Int), ("msgp", Ptr), ("msgsz", Size), ("msgtyp", Int), ("msgflg", Hex)], R::Int),
+    sc("msgctl", &[("msqid", Int), ("cmd", Int), ("buf", Ptr)], R::Int),
+    sc("mq_open", &[("name", Str), ("oflag", Flags(F::Open)), ("mode", Mode), ("attr", Ptr)], R::Fd),
+    sc("mq_unlink", &[("name", Str)], R::Int),
+    sc("mq_timedsend", MQ_TIMEDSEND, R::Int),
+    sc("mq_timedreceive", MQ_TIMEDRECEIVE, R::Int),
+    sc("mq_notify", &[("mqdes", Fd), ("sevp", Ptr)], R::Int),
+    sc("mq_getsetattr", &[("mqdes", Fd), ("newattr", Ptr), ("oldattr", Ptr)], R::Int),
+
+    // --- AIO e io_uring ---
+    sc("io_setup", &[("nr_events", Uint), ("ctx_idp", Ptr)], R::Int),
+    sc("io_destroy", &[("ctx_id", Hex)], R::Int),
+    sc("io_getevents", IO_GETEVENTS, R::Int),
+    sc("io_pgetevents", IO_PGETEVENTS, R::Int),
+    sc("io_submit", &[("ctx_id", Hex), ("nr", Int), ("iocbpp", Ptr)], R::Int),
+    sc("io_cancel", &[("ctx_id", Hex), ("iocb", Ptr), ("result", Ptr)], R::Int),
+    sc("io_uring_setup", &[("entries", Uint), ("p", Ptr)], R::Fd),
+    sc("io_uring_enter", &[("fd", Fd), ("to_submit", Uint), ("min_complete", Uint), ("flags", Hex), ("sig", Ptr), ("sz", Size)], R::Int),
+    sc("io_uring_register", &[("fd", Fd), ("opcode", Uint), ("arg", Ptr), ("nr_args", Uint)], R::Int),
+
+    // --- Sistema, montaje y módulos ---
+    sc("uname", &[("buf", Out(S::Utsname))], R::Int),
+    sc("sysinfo", &[("info", Out(S::Sysinfo))], R::Int),
+    sc("syslog", &[("type", Int), ("bufp", Ptr), ("len", Int)], R::Int),
+    sc("sysfs", &[("option", Int), ("arg1", Hex), ("arg2", Hex)], R::Int),
+    sc("sethostname", &[("name", BufIn(1)), ("len", Size)], R::Int),
+    sc("setdomainname", &[("name", BufIn(1)), ("len", Size)], R::Int),
+    sc("getrandom", &[("buf", BufOut(1)), ("buflen", Size), ("flags", Flags(F::Getrandom))], R::Int),
+    sc("reboot", &[("magic", Hex), ("magic2", Hex), ("cmd", Hex), ("arg", Ptr)], R::Int),
+    sc("acct", &[("filename", Path)], R::Int),
+    sc("mount", &[("source", Str), ("target", Path), ("filesystemtype", Str), ("mountflags", Hex), ("data", Ptr)], R::Int),
+    sc("umount2", &[("target", Path), ("flags", Hex)], R::Int),
+    sc("swapon", &[("path", Path), ("swapflags", Hex)], R::Int),
+    sc("swapoff", &[("path", Path)], R::Int),
+    sc("open_tree", &[("dirfd", DirFd), ("pathname", Path), ("flags", Hex)], R::Fd),
+    sc("move_mount", &[("from_dirfd", DirFd), ("from_pathname", Path), ("to_dirfd", DirFd), ("to_pathname", Path), ("flags", Hex)], R::Int),
+    sc("fsopen", &[("fsname", Str), ("flags", Hex)], R::Fd),
+    sc("fsconfig", &[("fd", Fd), ("cmd", Uint), ("key", Str), ("value", Ptr), ("aux", Int)], R::Int),
+    sc("fsmount", &[("fs_fd", Fd), ("flags", Hex), ("attr_flags", Hex)], R::Fd),
+    sc("fspick", &[("dirfd", DirFd), ("pathname", Path), ("flags", Hex)], R::Fd),
+    sc("mount_setattr", &[("dirfd", DirFd), ("pathname", Path), ("flags", Flags(F::At)), ("attr", Ptr), ("size", Size)], R::Int),
+    sc("statmount", &[("req", Ptr), ("buf", Ptr), ("bufsize", Size), ("flags", Hex)], R::Int),
+    sc("listmount", &[("req", Ptr), ("mnt_ids", Ptr), ("nr_mnt_ids", Size), ("flags", Hex)], R::Int),
+    sc("quotactl", &[("cmd", Hex), ("special", Path), ("id", Int), ("addr", Ptr)], R::Int),
+    sc("quotactl_fd", &[("fd", Fd), ("cmd", Hex), ("id", Int), ("addr", Ptr)], R::Int),
+    sc("init_module", &[("module_image", Ptr), ("len", Size), ("param_values", Str)], R::Int),
+    sc("finit_module", &[("fd", Fd), ("param_values", Str), ("flags", Hex)], R::Int),
+    sc("delete_module", &[("name", Str), ("flags", Hex)], R::Int),
+    sc("kexec_load", &[("entry", Hex), ("nr_segments", Uint), ("segments", Ptr), ("flags", Hex)], R::Int),
+    sc("kexec_file_load", &[("kernel_fd", Fd), ("initrd_fd", Fd), ("cmdline_len", Size), ("cmdline", Str), ("flags", Hex)], R::Int),
+    sc("bpf", &[("cmd", Int), ("attr", Ptr), ("size", Uint)], R::Int),
+    sc("perf_event_open", &[("attr", Ptr), ("pid", Pid), ("cpu", Int), ("group_fd", Fd), ("flags", Hex)], R::Fd),
+    sc("add_key", &[("type", Str), ("description", Str), ("payload", BufIn(3)), ("plen", Size), ("keyring", Int)], R::Int),
+    sc("request_key", &[("type", Str), ("description", Str), ("callout_info", Str), ("dest_keyring", Int)], R::Int),
+    sc("keyctl", &[("operation", Int), ("arg2", Hex), ("arg3", Hex), ("arg4", Hex), ("arg5", Hex)], R::Int),
+    sc("vhangup", NO_ARGS, R::Int),
+    sc("iopl", &[("level", Int)], R::Int),
+    sc("ioperm", &[("from", Hex), ("num", Uint), ("turn_on", Int)], R::Int),
+    sc("modify_ldt", &[("func", Int), ("ptr", Ptr), ("bytecount", Size)], R::Int),
+    sc("set_thread_area", &[("u_info", Ptr)], R::Int),
+    sc("get_thread_area", &[("u_info", Ptr)], R::Int),
+    sc("lookup_dcookie", &[("cookie", Hex), ("buffer", Ptr), ("len", Size)], R::Int),
+    sc("uselib", &[("library", Path)], R::Int),
+    sc("_sysctl", &[("args", Ptr)], R::Int),
+    sc("uretprobe", NO_ARGS, R::Int),
+
+    // --- Variantes de otras ABIs (i386 y genérica) ---
+    sc("mmap2", MMAP, R::Hex),
+    sc("_newselect", &[("nfds", Int), ("readfds", Ptr), ("writefds", Ptr), ("exceptfds", Ptr), ("timeout", In(S::Timeval))], R::Int),
+    sc("_llseek", &[("fd", Fd), ("offset_high", Uint), ("offset_low", Uint), ("result", Ptr), ("whence", Flags(F::Whence))], R::Int),
+    sc("stat64", STAT_PATH, R::Int),
+    sc("lstat64", STAT_PATH, R::Int),
+    sc("fstat64", &[("fd", Fd), ("statbuf", Out(S::Stat))], R::Int),
+    sc("fstatat64", STAT_AT, R::Int),
+    sc("fcntl64", FCNTL, R::Int),
+    sc("statfs64", &[("path", Path), ("sz", Size), ("buf", Out(S::Statfs))], R::Int),
+    sc("fstatfs64", &[("fd", Fd), ("sz", Size), ("buf", Out(S::Statfs))], R::Int),
+    // i386 pasa los valores de 64 bits partidos en dos registros (mitad baja primero)
+    sc("truncate64", &[("path", Path), ("length_low", Uint), ("length_high", Uint)], R::Int),
+    sc("ftruncate64", &[("fd", Fd), ("length_low", Uint), ("length_high", Uint)], R::Int),
+    sc("fadvise64_64", &[("fd", Fd), ("offset_low", Uint), ("offset_high", Uint), ("len_low", Uint), ("len_high", Uint), ("advice", Int)], R::Int),
+    sc("sendfile64", SENDFILE, R::Int),
+    sc("ugetrlimit", GETRLIMIT, R::Int),
+    sc("waitpid", &[("pid", Pid), ("wstatus", Out(S::WaitStatus)), ("options", Flags(F::Wait))], R::Pid),
+    sc("socketcall", &[("call", Int), ("args", Ptr)], R::Int),
+    sc("ipc", &[("call", Uint), ("first", Int), ("second", Int), ("third", Int), ("ptr", Ptr), ("fifth", Int)], R::Int),
+    sc("getuid32", NO_ARGS, R::Int),
+    sc("geteuid32", NO_ARGS, R::Int),
+    sc("getgid32", NO_ARGS, R::Int),
+    sc("getegid32", NO_ARGS, R::Int),
+    sc("setuid32", ONE_UID, R::Int),
+    sc("setgid32", ONE_UID, R::Int),
+    sc("chown32", CHOWN, R::Int),
+    sc("lchown32", CHOWN, R::Int),
+    sc("fchown32", FCHOWN, R::Int),
+    sc("clock_gettime64", CLOCK_TS_OUT, R::Int),
+    sc("clock_settime64", CLOCK_TS_IN, R::Int),
+    sc("clock_getres_time64", CLOCK_TS_OUT, R::Int),
+    sc("clock_nanosleep_time64", CLOCK_NANOSLEEP, R::Int),
+    sc("futex_time64", FUTEX, R::Int),
+    sc("ppoll_time64", PPOLL, R::Int),
+    sc("pselect6_time64", PSELECT, R::Int),
+    sc("utimensat_time64", UTIMENSAT, R::Int),
+    sc("rt_sigtimedwait_time64", SIGTIMEDWAIT, R::Int),
+    sc("io_pgetevents_time64", IO_PGETEVENTS, R::Int),
+    sc("recvmmsg_time64", RECVMMSG, R::Int),
+    sc("mq_timedsend_time64", MQ_TIMEDSEND, R::Int),
+    sc("mq_timedreceive_time64", MQ_TIMEDRECEIVE, R::Int),
+    sc("semtimedop_time64", SEMTIMEDOP, R::Int),
+    sc("timer_settime64", TIMER_SETTIME, R::Int),
+    sc("timer_gettime64", TIMER_GETTIME, R::Int),
+    sc("timerfd_settime64", TIMERFD_SETTIME, R::Int),
+    sc("timerfd_gettime64", TIMERFD_GETTIME, R::Int),
+];
+
+// Syscalls cuya firma cambia según la ABI aunque se llamen igual. En i386, mmap (90) y
+// select (82) son las versiones antiguas que reciben un único puntero a struct
+// (mmap_arg_struct, sel_arg_struct); i386 y aarch64 usan además el orden
+// CLONE_BACKWARDS de clone, con tls antes que child_tid. Los offsets y longitudes de
+// 64 bits llegan en i386 partidos en dos argumentos de 32 bits (mitad baja primero), y
+// se muestran así, como en _llseek.
+const CLONE_BACKWARDS: &[(&str, ArgType)] =
+    &[("flags", Flags(F::Clone)), ("stack", Ptr), ("parent_tid", Ptr), ("tls", Hex), ("child_tid", Ptr)];
+
+#[rustfmt::skip]
+static OVERRIDES: &[(Abi, SyscallSpec)] = &[
+    (Abi::I386, sc("mmap", &[("args", Ptr)], R::Hex)),
+    (Abi::I386, sc("select", &[("args", Ptr)], R::Int)),
+    (Abi::I386, sc("clone", CLONE_BACKWARDS, R::Pid)),
+    (Abi::I386, sc("pread64", &[("fd", Fd), ("buf", BufOut(2)), ("count", Size), ("pos_low", Uint), ("pos_high", Uint)], R::Int)),
+    (Abi::I386, sc("pwrite64", &[("fd", Fd), ("buf", BufIn(2)), ("count", Size), ("pos_low", Uint), ("pos_high", Uint)], R::Int)),
+    (Abi::I386, sc("readahead", &[("fd", Fd), ("offset_low", Uint), ("offset_high", Uint), ("count", Size)], R::Int)),
+    (Abi::I386, sc("fadvise64", &[("fd", Fd), ("offset_low", Uint), ("offset_high", Uint), ("len", Size), ("advice", Int)], R::Int)),
+    (Abi::I386, sc("fallocate", &[("fd", Fd), ("mode", Hex), ("offset_low", Uint), ("offset_high", Uint), ("len_low", Uint), ("len_high", Uint)], R::Int)),
+    (Abi::I386, sc("sync_file_range", &[("fd", Fd), ("offset_low", Uint), ("offset_high", Uint), ("nbytes_low", Uint), ("nbytes_high", Uint), ("flags", Hex)], R::Int)),
+    (Abi::Aarch64, sc("clone", CLONE_BACKWARDS, R::Pid)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg_names(abi: Abi, name: &str) -> Vec<&'static str> {
+        syscall_spec(abi, name).unwrap().args.iter().map(|&(n, _)| n).collect()
+    }
+
+    #[test]
+    fn firmas_por_abi() {
+        assert_eq!(arg_names(Abi::X86_64, "mmap").len(), 6);
+        assert_eq!(arg_names(Abi::Aarch64, "mmap").len(), 6);
+        assert_eq!(arg_names(Abi::I386, "mmap"), ["args"]);
+        assert_eq!(arg_names(Abi::I386, "mmap2").len(), 6);
+        assert_eq!(arg_names(Abi::I386, "select"), ["args"]);
+        assert_eq!(arg_names(Abi::X86_64, "select").len(), 5);
+        assert_eq!(arg_names(Abi::X86_64, "clone"), ["flags", "stack", "parent_tid", "child_tid", "tls"]);
+        assert_eq!(arg_names(Abi::I386, "clone"), ["flags", "stack", "parent_tid", "tls", "child_tid"]);
+        assert_eq!(arg_names(Abi::Aarch64, "clone"), ["flags", "stack", "parent_tid", "tls", "child_tid"]);
+
+        // Valores de 64 bits partidos en i386
+        assert_eq!(arg_names(Abi::X86_64, "pread64"), ["fd", "buf", "count", "offset"]);
+        assert_eq!(arg_names(Abi::I386, "pread64"), ["fd", "buf", "count", "pos_low", "pos_high"]);
+        assert_eq!(arg_names(Abi::I386, "pwrite64"), ["fd", "buf", "count", "pos_low", "pos_high"]);
+        assert_eq!(arg_names(Abi::I386, "truncate64"), ["path", "length_low", "length_high"]);
+        assert_eq!(arg_names(Abi::I386, "ftruncate64"), ["fd", "length_low", "length_high"]);
+        assert_eq!(arg_names(Abi::I386, "fadvise64_64").len(), 6);
+        assert_eq!(arg_names(Abi::I386, "fallocate"), ["fd", "mode", "offset_low", "offset_high", "len_low", "len_high"]);
+        assert_eq!(arg_names(Abi::Aarch64, "fallocate"), ["fd", "mode", "offset", "len"]);
+        assert_eq!(arg_names(Abi::I386, "sync_file_range").len(), 6);
+        assert_eq!(arg_names(Abi::X86_64, "sync_file_range").len(), 4);
+    }
+}
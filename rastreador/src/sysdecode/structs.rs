@@ -0,0 +1,232 @@
+use nix::unistd::Pid;
+use std::io;
+use std::mem::size_of;
+
+use super::flags::fmt_sa_flags;
+use super::schema::StructKind;
+use super::{escape_bytes, read_bytes, read_c_string, signal_name};
+
+// === Decodificación de structs del tracee ===
+// Las structs cuyo layout depende de la ABI se leen con los tipos de libc del host,
+// así que solo valen para la ABI nativa (lo comprueba el llamador con `abi_independent`).
+
+const MAX_DIRENTS: usize = 8; // entradas mostradas de getdents64
+const MAX_DIRENT_BYTES: usize = 8192; // bytes leídos del buffer de getdents64
+
+/// Structs con el mismo layout en todas las ABIs (se decodifican también en i386).
+pub fn abi_independent(kind: StructKind) -> bool {
+    matches!(kind, StructKind::FdPair | StructKind::WaitStatus | StructKind::Dirents64 | StructKind::CStr)
+}
+
+/// Lee `n` palabras nativas (long) consecutivas.
+pub fn read_words(pid: Pid, addr: u64, n: usize) -> io::Result<Vec<u64>> {
+    let bytes = read_bytes(pid, addr, n * 8)?;
+    Ok(bytes.chunks_exact(8).map(|c| u64::from_ne_bytes(c.try_into().unwrap())).collect())
+}
+
+/// Lee una struct de libc completa desde la memoria del tracee.
+fn read_struct<T: Copy>(pid: Pid, addr: u64) -> Option<T> {
+    let bytes = read_bytes(pid, addr, size_of::<T>()).ok()?;
+    // SAFETY: T es una struct POD de libc; cualquier patrón de bits es un valor válido
+    Some(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const T) })
+}
+
+/// Decodifica una struct; None si no se pudo leer o no se sabe mostrar. `ret` es el
+/// retorno de la syscall (bytes válidos en getdents64; 0 en la entrada).
+pub fn fmt_struct(pid: Pid, kind: StructKind, addr: u64, ret: i64) -> Option<String> {
+    let s = match kind {
+        StructKind::Timespec => {
+            let w = read_words(pid, addr, 2).ok()?;
+            format!("{{tv_sec={}, tv_nsec={}}}", w[0] as i64, w[1] as i64)
+        }
+        StructKind::Timeval => {
+            let w = read_words(pid, addr, 2).ok()?;
+            format!("{{tv_sec={}, tv_usec={}}}", w[0] as i64, w[1] as i64)
+        }
+        StructKind::Itimerval => {
+            let w = read_words(pid, addr, 4).ok()?;
+            format!(
+                "{{it_interval={{tv_sec={}, tv_usec={}}}, it_value={{tv_sec={}, tv_usec={}}}}}",
+                w[0] as i64, w[1] as i64, w[2] as i64, w[3] as i64
+            )
+        }
+        StructKind::Sigset => fmt_sigset(read_words(pid, addr, 1).ok()?[0]),
+        StructKind::Sigaction => {
+            // struct sigaction del kernel: handler, flags, restorer, mask
+            let w = read_words(pid, addr, 4).ok()?;
+            let handler = match w[0] {
+                0 => "SIG_DFL".to_string(),
+                1 => "SIG_IGN".to_string(),
+                h => format!("0x{:x}", h),
+            };
+            format!("{{sa_handler={}, sa_mask={}, sa_flags={}}}", handler, fmt_sigset(w[3]), fmt_sa_flags(w[1]))
+        }
+        StructKind::Rlimit => {
+            let w = read_words(pid, addr, 2).ok()?;
+            format!("{{rlim_cur={}, rlim_max={}}}", fmt_rlim(w[0]), fmt_rlim(w[1]))
+        }
+        StructKind::FdPair => {
+            let fds: [i32; 2] = read_struct(pid, addr)?;
+            format!("[{}, {}]", fds[0], fds[1])
+        }
+        StructKind::Stat => {
+            let st: libc::stat = read_struct(pid, addr)?;
+            format!(
+                "{{st_dev=0x{:x}, st_ino={}, st_mode={}, st_nlink={}, st_size={}}}",
+                st.st_dev,
+                st.st_ino,
+                fmt_file_mode(st.st_mode),
+                st.st_nlink,
+                st.st_size
+            )
+        }
+        StructKind::Statx => {
+            let stx: libc::statx = read_struct(pid, addr)?;
+            format!(
+                "{{stx_mask=0x{:x}, stx_ino={}, stx_mode={}, stx_nlink={}, stx_size={}}}",
+                stx.stx_mask,
+                stx.stx_ino,
+                fmt_file_mode(stx.stx_mode as u32),
+                stx.stx_nlink,
+                stx.stx_size
+            )
+        }
+        StructKind::Statfs => {
+            let sf: libc::statfs = read_struct(pid, addr)?;
+            format!(
+                "{{f_type=0x{:x}, f_bsize={}, f_blocks={}, f_bfree={}, f_bavail={}}}",
+                sf.f_type, sf.f_bsize, sf.f_blocks, sf.f_bfree, sf.f_bavail
+            )
+        }
+        StructKind::Utsname => {
+            let u: libc::utsname = read_struct(pid, addr)?;
+            format!(
+                "{{sysname={}, nodename={}, release={}, machine={}}}",
+                cstr_field(&u.sysname),
+                cstr_field(&u.nodename),
+                cstr_field(&u.release),
+                cstr_field(&u.machine)
+            )
+        }
+        StructKind::Sysinfo => {
+            let si: libc::sysinfo = read_struct(pid, addr)?;
+            format!(
+                "{{uptime={}, totalram={}, freeram={}, procs={}, mem_unit={}}}",
+                si.uptime, si.totalram, si.freeram, si.procs, si.mem_unit
+            )
+        }
+        StructKind::Rusage => {
+            let ru: libc::rusage = read_struct(pid, addr)?;
+            format!(
+                "{{ru_utime={{tv_sec={}, tv_usec={}}}, ru_stime={{tv_sec={}, tv_usec={}}}, ru_maxrss={}}}",
+                ru.ru_utime.tv_sec, ru.ru_utime.tv_usec, ru.ru_stime.tv_sec, ru.ru_stime.tv_usec, ru.ru_maxrss
+            )
+        }
+        StructKind::Tms => {
+            let t: libc::tms = read_struct(pid, addr)?;
+            format!(
+                "{{tms_utime={}, tms_stime={}, tms_cutime={}, tms_cstime={}}}",
+                t.tms_utime, t.tms_stime, t.tms_cutime, t.tms_cstime
+            )
+        }
+        // Con WNOHANG y sin hijos listos (retorno 0) el kernel no escribe el estado
+        StructKind::WaitStatus if ret == 0 => return None,
+        StructKind::WaitStatus => fmt_wait_status(read_struct(pid, addr)?),
+        StructKind::Dirents64 => fmt_dirents64(pid, addr, ret as usize)?,
+        StructKind::CStr => escape_bytes(&read_c_string(pid, addr).ok()?),
+    };
+    Some(s)
+}
+
+fn fmt_rlim(v: u64) -> String {
+    if v == u64::MAX { "RLIM_INFINITY".to_string() } else { format!("{}", v) }
+}
+
+/// Conjunto de señales como `[SIGINT SIGTERM]`; si hay más de la mitad, `~[...]`
+/// con las que faltan.
+fn fmt_sigset(mask: u64) -> String {
+    let (mask, prefix) = if mask.count_ones() > 32 { (!mask, "~") } else { (mask, "") };
+    let names: Vec<String> = (0..64).filter(|b| mask & (1 << b) != 0).map(|b| signal_name(b + 1)).collect();
+    format!("{}[{}]", prefix, names.join(" "))
+}
+
+/// Tipo de archivo más permisos: `S_IFREG|0644`.
+fn fmt_file_mode(mode: u32) -> String {
+    let kind = match mode & libc::S_IFMT {
+        libc::S_IFREG => "S_IFREG",
+        libc::S_IFDIR => "S_IFDIR",
+        libc::S_IFLNK => "S_IFLNK",
+        libc::S_IFCHR => "S_IFCHR",
+        libc::S_IFBLK => "S_IFBLK",
+        libc::S_IFIFO => "S_IFIFO",
+        libc::S_IFSOCK => "S_IFSOCK",
+        _ => return format!("0{:o}", mode),
+    };
+    format!("{}|0{:o}", kind, mode & !libc::S_IFMT)
+}
+
+fn cstr_field(field: &[libc::c_char]) -> String {
+    let bytes: Vec<u8> = field.iter().take_while(|&&c| c != 0).map(|&c| c.to_ne_bytes()[0]).collect();
+    escape_bytes(&bytes)
+}
+
+/// Estado de wait con las macros de wait(2), como lo muestra strace.
+fn fmt_wait_status(status: i32) -> String {
+    if libc::WIFEXITED(status) {
+        format!("[{{WIFEXITED(s) && WEXITSTATUS(s) == {}}}]", libc::WEXITSTATUS(status))
+    } else if libc::WIFSIGNALED(status) {
+        let core = if libc::WCOREDUMP(status) { " && WCOREDUMP(s)" } else { "" };
+        format!("[{{WIFSIGNALED(s) && WTERMSIG(s) == {}{}}}]", signal_name(libc::WTERMSIG(status)), core)
+    } else if libc::WIFSTOPPED(status) {
+        format!("[{{WIFSTOPPED(s) && WSTOPSIG(s) == {}}}]", signal_name(libc::WSTOPSIG(status)))
+    } else if libc::WIFCONTINUED(status) {
+        "[{WIFCONTINUED(s)}]".to_string()
+    } else {
+        format!("[0x{:x}]", status)
+    }
+}
+
+/// Entradas de linux_dirent64 { u64 d_ino; s64 d_off; u16 d_reclen; u8 d_type; char d_name[]; }
+fn fmt_dirents64(pid: Pid, addr: u64, len: usize) -> Option<String> {
+    let bytes = read_bytes(pid, addr, len.min(MAX_DIRENT_BYTES)).ok()?;
+    let mut items = Vec::new();
+    let mut total = 0;
+    let mut off = 0;
+    while off + 19 <= bytes.len() {
+        let reclen = u16::from_ne_bytes([bytes[off + 16], bytes[off + 17]]) as usize;
+        if reclen == 0 || off + reclen > bytes.len() {
+            break;
+        }
+        total += 1;
+        if items.len() < MAX_DIRENTS {
+            let ino = u64::from_ne_bytes(bytes[off..off + 8].try_into().unwrap());
+            let name = &bytes[off + 19..off + reclen];
+            let end = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+            items.push(format!(
+                "{{d_ino={}, d_type={}, d_name={}}}",
+                ino,
+                dirent_type(bytes[off + 18]),
+                escape_bytes(&name[..end])
+            ));
+        }
+        off += reclen;
+    }
+    // Si se truncó la lectura no se sabe cuántas entradas hay en total
+    if total > items.len() || len > bytes.len() {
+        items.push("...".to_string());
+    }
+    Some(format!("[{}]", items.join(", ")))
+}
+
+fn dirent_type(t: u8) -> &'static str {
+    match t {
+        libc::DT_REG => "DT_REG",
+        libc::DT_DIR => "DT_DIR",
+        libc::DT_LNK => "DT_LNK",
+        libc::DT_CHR => "DT_CHR",
+        libc::DT_BLK => "DT_BLK",
+        libc::DT_FIFO => "DT_FIFO",
+        libc::DT_SOCK => "DT_SOCK",
+        _ => "DT_UNKNOWN",
+    }
+}